@@ -0,0 +1,441 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{
+    log::log,
+    media::{Media, OutputPolicy, VideoCodec},
+};
+
+const APP_DIR: &str = "media-tweak";
+
+/// the directory for files that persist between sessions, like `~/.config/media-tweak`
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR))
+}
+
+/// the directory for files that can be regenerated, like `~/.cache/media-tweak`
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(APP_DIR))
+}
+
+pub fn thumbnail_dir() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join("thumbnails"))
+}
+
+pub fn settings_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("settings.json"))
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("history.jsonl"))
+}
+
+pub fn queue_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("queue.json"))
+}
+
+/// reads a JSON file, using the default if there is none yet or it can't be read.
+/// a corrupt file is moved aside instead of being overwritten by the next save
+fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return T::default(),
+        Err(e) => {
+            log!("failed to read {}, using the defaults: {e}", path.display());
+            return T::default();
+        }
+    };
+
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        let backup = path.with_extension("json.corrupt");
+        log!(
+            "{} is corrupt, moving it to {} and using the defaults: {e}",
+            path.display(),
+            backup.display()
+        );
+        if let Err(e) = fs::rename(path, &backup) {
+            log!("failed to move {} aside: {e}", path.display());
+        }
+        T::default()
+    })
+}
+
+/// writes to a temporary file first, so a crash can't leave half the file behind
+fn save_json(value: &impl Serialize, path: &Path) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let contents = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(temp, path)
+}
+
+/// preferences that persist between sessions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub default_streams: DefaultStreams,
+    /// create the output's folder when it's missing, instead of asking first
+    pub create_output_dirs: bool,
+    /// what to do when the output already exists
+    pub output_policy: OutputPolicy,
+
+    /// the first file the last successful run wrote
+    pub last_output: Option<PathBuf>,
+    /// offer to load the last output on launch, for trimming it again
+    pub offer_last_output: bool,
+
+    /// show every setting, instead of only what a quick trim needs
+    pub advanced_view: bool,
+
+    /// run after every successful export, with `{output}` replaced by each output's path.
+    /// empty to run nothing
+    pub post_export_command: String,
+
+    /// seconds a mouse wheel notch moves a time slider by, before the modifiers
+    pub scroll_step: f64,
+
+    /// the encoders to try in order when a hardware encoder fails, skipping any this build lacks
+    pub encoder_fallbacks: Vec<VideoCodec>,
+
+    pub keymap: Keymap,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            default_streams: DefaultStreams::default(),
+            create_output_dirs: false,
+            output_policy: OutputPolicy::default(),
+            last_output: None,
+            offer_last_output: true,
+            advanced_view: false,
+            post_export_command: String::new(),
+            scroll_step: 1.0,
+            encoder_fallbacks: vec![VideoCodec::Libx264],
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// reads the settings, using the defaults if there are none yet or they can't be read.
+    /// a corrupt file is moved aside instead of being overwritten by the next save
+    pub fn load() -> Self {
+        match settings_path() {
+            Some(path) => Self::load_from(&path),
+            None => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        load_json(path)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = settings_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        self.save_to(&path)
+    }
+
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        save_json(self, path)
+    }
+}
+
+/// how far a queued job got, where a job that finished leaves the queue
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    #[default]
+    Pending,
+    Running,
+    /// it was running when the last session ended, so it runs again from the start
+    Interrupted,
+    /// with why, and it's skipped until it's queued again
+    Failed(String),
+}
+
+impl JobState {
+    pub fn is_runnable(&self) -> bool {
+        matches!(self, JobState::Pending | JobState::Interrupted)
+    }
+}
+
+/// a job waiting in the queue
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueuedJob {
+    pub media: Media,
+    pub state: JobState,
+}
+
+/// jobs that run one after another, kept on disk so a crash or closing the window doesn't lose them
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Queue {
+    pub jobs: Vec<QueuedJob>,
+}
+
+impl Queue {
+    /// reads the queue the last session left, where the job that was running never finished
+    pub fn load() -> Self {
+        match queue_path() {
+            Some(path) => Self::load_from(&path),
+            None => Self::default(),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let mut queue: Self = load_json(path);
+        for job in &mut queue.jobs {
+            if job.state == JobState::Running {
+                job.state = JobState::Interrupted;
+            }
+        }
+        queue
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = queue_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        self.save_to(&path)
+    }
+
+    /// an empty queue removes the file, so there's nothing to offer on the next launch
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if !self.jobs.is_empty() {
+            return save_json(self, path);
+        }
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// the next job to run, which is the first that didn't run or was interrupted
+    pub fn next_job(&mut self) -> Option<&mut QueuedJob> {
+        self.jobs.iter_mut().find(|job| job.state.is_runnable())
+    }
+
+    /// the jobs that still have to run, in order
+    pub fn runnable(&self) -> impl Iterator<Item = &QueuedJob> {
+        self.jobs.iter().filter(|job| job.state.is_runnable())
+    }
+
+    /// moves a job to another place in the queue, unless either place holds the running job
+    pub fn move_job(&mut self, from: usize, to: usize) -> bool {
+        let movable = |index: usize| {
+            self.jobs
+                .get(index)
+                .is_some_and(|job| job.state != JobState::Running)
+        };
+        if from == to || !movable(from) || !movable(to) {
+            return false;
+        }
+        let job = self.jobs.remove(from);
+        self.jobs.insert(to, job);
+        true
+    }
+
+    /// the index of the running job, which is the only one at a time
+    pub fn running(&self) -> Option<usize> {
+        self.jobs
+            .iter()
+            .position(|job| job.state == JobState::Running)
+    }
+}
+
+/// the single keys that act when nothing else takes the keyboard, where an empty key is unbound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    pub toggle_video: String,
+    pub toggle_audio: String,
+    pub toggle_subs: String,
+    pub toggle_extra_streams: String,
+    pub toggle_console: String,
+    pub swap_boundaries: String,
+    pub quit: String,
+    /// Escape also quits, unless it's closing the palette
+    pub escape_quits: bool,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            toggle_video: "v".to_string(),
+            toggle_audio: "a".to_string(),
+            toggle_subs: "s".to_string(),
+            toggle_extra_streams: "e".to_string(),
+            toggle_console: "`".to_string(),
+            swap_boundaries: "x".to_string(),
+            quit: "q".to_string(),
+            escape_quits: true,
+        }
+    }
+}
+
+impl Keymap {
+    /// the action bound to a typed character, the first one listed if it's bound twice
+    pub fn hotkey(&self, key: &str) -> Option<Hotkey> {
+        [
+            (&self.toggle_video, Hotkey::ToggleVideo),
+            (&self.toggle_audio, Hotkey::ToggleAudio),
+            (&self.toggle_subs, Hotkey::ToggleSubs),
+            (&self.toggle_extra_streams, Hotkey::ToggleExtraStreams),
+            (&self.toggle_console, Hotkey::ToggleConsole),
+            (&self.swap_boundaries, Hotkey::SwapBoundaries),
+            (&self.quit, Hotkey::Quit),
+        ]
+        .into_iter()
+        .find(|(bound, _)| !bound.is_empty() && bound.as_str() == key)
+        .map(|(_, hotkey)| hotkey)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hotkey {
+    ToggleVideo,
+    ToggleAudio,
+    ToggleSubs,
+    ToggleExtraStreams,
+    ToggleConsole,
+    SwapBoundaries,
+    Quit,
+}
+
+/// which streams start out enabled when an input is loaded, if the input has them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultStreams {
+    pub video: bool,
+    pub audio: bool,
+    pub subs: bool,
+    pub extra_streams: bool,
+}
+
+impl Default for DefaultStreams {
+    fn default() -> Self {
+        Self {
+            video: true,
+            audio: true,
+            subs: true,
+            extra_streams: true,
+        }
+    }
+}
+
+impl DefaultStreams {
+    /// turns off the streams that shouldn't start out enabled, since detection turns on every stream that exists
+    pub fn apply(&self, media: &mut Media) {
+        media.use_video &= self.video;
+        media.use_audio &= self.audio;
+        media.use_subs &= self.subs;
+        media.use_extra_streams &= self.extra_streams;
+    }
+
+    pub fn toggle(&mut self, kind: StreamKind) {
+        let enabled = match kind {
+            StreamKind::Video => &mut self.video,
+            StreamKind::Audio => &mut self.audio,
+            StreamKind::Subs => &mut self.subs,
+            StreamKind::ExtraStreams => &mut self.extra_streams,
+        };
+        *enabled = !*enabled;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    Video,
+    Audio,
+    Subs,
+    ExtraStreams,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keymap_skips_unbound_keys() {
+        let keymap = Keymap {
+            quit: String::new(),
+            toggle_console: "q".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(keymap.hotkey("q"), Some(Hotkey::ToggleConsole));
+        assert_eq!(keymap.hotkey("v"), Some(Hotkey::ToggleVideo));
+        assert_eq!(keymap.hotkey(""), None);
+        assert_eq!(keymap.hotkey("`"), None);
+    }
+
+    #[test]
+    fn restored_queue_marks_the_running_job_interrupted() {
+        let path =
+            std::env::temp_dir().join(format!("media-tweak-queue-{}.json", std::process::id()));
+        let job = |state| QueuedJob {
+            media: Media {
+                input: "clip.mkv".to_string(),
+                ..Default::default()
+            },
+            state,
+        };
+        let queue = Queue {
+            jobs: vec![
+                job(JobState::Running),
+                job(JobState::Pending),
+                job(JobState::Failed("no space left".to_string())),
+            ],
+        };
+        queue.save_to(&path).unwrap();
+
+        let mut restored = Queue::load_from(&path);
+        assert_eq!(restored.jobs[0].state, JobState::Interrupted);
+        assert_eq!(restored.jobs[1..], queue.jobs[1..]);
+        assert_eq!(restored.running(), None);
+        // the interrupted job runs again first
+        assert_eq!(restored.next_job().unwrap().state, JobState::Interrupted);
+
+        // the running job stays where it is
+        restored.jobs[0].state = JobState::Running;
+        assert!(!restored.move_job(1, 0));
+        assert!(restored.move_job(2, 1));
+        assert_eq!(
+            restored.jobs[1].state,
+            JobState::Failed("no space left".to_string())
+        );
+
+        // an empty queue leaves nothing to restore
+        Queue::default().save_to(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn settings_keep_the_output_policy() {
+        let path =
+            std::env::temp_dir().join(format!("media-tweak-settings-{}.json", std::process::id()));
+
+        // settings from before the policy was saved still load, asking first
+        fs::write(&path, r#"{"scroll_step": 2.0}"#).unwrap();
+        let settings = Settings::load_from(&path);
+        assert_eq!(settings.output_policy, OutputPolicy::Ask);
+        assert_eq!(settings.scroll_step, 2.0);
+
+        let settings = Settings {
+            output_policy: OutputPolicy::Version,
+            ..settings
+        };
+        settings.save_to(&path).unwrap();
+        assert_eq!(Settings::load_from(&path), settings);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+pub async fn pick_file() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .pick_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+pub async fn pick_files() -> Vec<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .pick_files()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .map(|file| file.path().to_path_buf())
+        .collect()
+}
+pub async fn pick_folder() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .pick_folder()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+/// asks where to save a file, suggesting a name
+pub async fn pick_save_path(name: String) -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_file_name(name)
+        .save_file()
+        .await
+        .map(|file| file.path().to_path_buf())
+}
+
+/// asks whether an existing output should be replaced
+pub async fn confirm_overwrite(path: PathBuf) -> bool {
+    rfd::AsyncMessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("Output already exists")
+        .set_description(format!("{} already exists, overwrite it?", path.display()))
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        .await
+        == rfd::MessageDialogResult::Yes
+}
+
+/// asks whether a file should be replaced by a rewritten copy of itself
+pub async fn confirm_rewrite(path: PathBuf) -> bool {
+    rfd::AsyncMessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("Rewrite the input")
+        .set_description(format!(
+            "{} will be replaced by a copy with the new metadata, continue?",
+            path.display()
+        ))
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        .await
+        == rfd::MessageDialogResult::Yes
+}
+
+/// asks whether a missing output folder should be created
+pub async fn confirm_create_dir(dir: PathBuf) -> bool {
+    rfd::AsyncMessageDialog::new()
+        .set_level(rfd::MessageLevel::Info)
+        .set_title("Output folder doesn't exist")
+        .set_description(format!("{} doesn't exist, create it?", dir.display()))
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show()
+        .await
+        == rfd::MessageDialogResult::Yes
+}
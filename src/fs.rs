@@ -1,37 +1,401 @@
-use std::{ffi::OsStr, path::PathBuf};
+use std::{
+    env,
+    ffi::{OsStr, OsString},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
-pub async fn pick_file() -> Option<PathBuf> {
-    rfd::AsyncFileDialog::new()
-        .pick_file()
-        .await
-        .map(|file| file.path().to_path_buf())
-}
-pub async fn pick_folder() -> Option<PathBuf> {
-    rfd::AsyncFileDialog::new()
-        .pick_folder()
-        .await
-        .map(|file| file.path().to_path_buf())
-}
+use serde::Serialize;
+use smol::process::Command;
+
+use crate::{
+    config::history_path,
+    log::log,
+    media::{Media, secs, split_args},
+};
 
-/// returns a path with a different filename
+/// the history log gets trimmed to its newer half once it grows past this many bytes
+const HISTORY_SIZE_CAP: u64 = 1024 * 1024;
+
+/// returns a path with a different filename.
+/// the suffix isn't doubled for an input that already has one, like a previous output
 pub async fn modify_path(mut path: PathBuf) -> PathBuf {
     path.set_file_name(format!(
         "{}_edited.{}",
-        path.file_stem()
-            .unwrap_or_else(|| OsStr::new("media"))
-            .to_str()
-            .unwrap_or_else(|| {
-                eprintln!("Failed to decode file_stem");
-                ""
-            }),
+        strip_edited_suffix(
+            path.file_stem()
+                .unwrap_or_else(|| OsStr::new("media"))
+                .to_str()
+                .unwrap_or_else(|| {
+                    log!("Failed to decode file_stem");
+                    ""
+                })
+        ),
         path.extension()
             .unwrap_or_else(|| OsStr::new("mkv"))
             .to_str()
             .unwrap_or_else(|| {
-                eprintln!("Failed to decode extension");
+                log!("Failed to decode extension");
                 ""
             })
     ));
 
     path
 }
+
+/// removes the suffix `modify_path` adds, along with the number `versioned_path` adds after it
+fn strip_edited_suffix(stem: &str) -> &str {
+    let unversioned = stem
+        .rsplit_once('_')
+        .filter(|(_, version)| !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit()))
+        .map_or(stem, |(rest, _)| rest);
+
+    match unversioned.strip_suffix("_edited") {
+        Some(rest) if !rest.is_empty() => rest,
+        _ => stem,
+    }
+}
+
+/// returns an image path next to the input for its contact sheet, like `clip_sheet.jpg`,
+/// numbered instead if that already exists
+pub fn sheet_path(input: &Path) -> PathBuf {
+    let mut name = input
+        .file_stem()
+        .unwrap_or_else(|| OsStr::new("media"))
+        .to_os_string();
+    name.push("_sheet.jpg");
+
+    let path = input.with_file_name(name);
+    if path.exists() {
+        versioned_path(&path, Path::exists)
+    } else {
+        path
+    }
+}
+
+/// returns a folder next to the input for stills exported from it, like `clip_stills`,
+/// numbered instead if that already exists
+pub fn stills_dir(input: &Path) -> PathBuf {
+    let mut name = input
+        .file_stem()
+        .unwrap_or_else(|| OsStr::new("media"))
+        .to_os_string();
+    name.push("_stills");
+
+    let path = input.with_file_name(name);
+    if path.exists() {
+        versioned_path(&path, Path::exists)
+    } else {
+        path
+    }
+}
+
+/// a path in the temporary directory for a sample of the output, keeping its container
+pub fn sample_path(output: &Path) -> PathBuf {
+    let mut name = OsString::from("media-tweak-sample.");
+    name.push(output.extension().unwrap_or_else(|| OsStr::new("mkv")));
+    env::temp_dir().join(name)
+}
+
+/// a path in the temporary directory for the preview proxy, separate for each running instance
+pub fn proxy_path() -> PathBuf {
+    env::temp_dir().join(format!("media-tweak-proxy-{}.mkv", std::process::id()))
+}
+
+/// a path next to `path` to write its replacement to before renaming it over,
+/// keeping the extension so ffmpeg picks the same muxer
+pub fn replacement_path(path: &Path) -> PathBuf {
+    let mut name = path
+        .file_stem()
+        .unwrap_or_else(|| OsStr::new("media"))
+        .to_os_string();
+    name.push(".media-tweak-tmp");
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+/// a file that is deleted once it is dropped
+#[derive(Debug)]
+pub struct TempFile(pub PathBuf);
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.0)
+            && e.kind() != io::ErrorKind::NotFound
+        {
+            log!("failed to remove {}: {e}", self.0.display());
+        }
+    }
+}
+
+/// returns a path with a part number appended to the filename, like `clip_part01.mkv`
+pub fn part_path(path: &Path, part: &str) -> PathBuf {
+    let mut name = path
+        .file_stem()
+        .unwrap_or_else(|| OsStr::new("media"))
+        .to_os_string();
+    name.push(format!("_part{part}"));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    path.with_file_name(name)
+}
+
+/// the folder a path would be written to, if it doesn't exist yet
+pub fn missing_dir(path: &Path) -> Option<PathBuf> {
+    path.parent()
+        .filter(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+        .map(Path::to_path_buf)
+}
+
+pub fn create_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| match e.kind() {
+        io::ErrorKind::PermissionDenied => {
+            format!("no permission to create the folder {}", dir.display())
+        }
+        _ => format!("failed to create the folder {}: {e}", dir.display()),
+    })
+}
+
+/// appends the first free number to the filename, like `clip_1.mkv`, `clip_2.mkv`
+pub fn versioned_path(path: &Path, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .unwrap_or_else(|| OsStr::new("media"))
+        .to_os_string();
+
+    (1..)
+        .map(|version| {
+            let mut name = stem.clone();
+            name.push(format!("_{version}"));
+            if let Some(extension) = path.extension() {
+                name.push(".");
+                name.push(extension);
+            }
+            path.with_file_name(name)
+        })
+        .find(|path| !taken(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// gives `to` the modification time of `from`, so trimmed files keep their place when sorted by date
+pub fn copy_mtime(from: &Path, to: &Path) -> io::Result<()> {
+    let modified = fs::metadata(from)?.modified()?;
+    OpenOptions::new()
+        .write(true)
+        .open(to)?
+        .set_modified(modified)
+}
+
+/// one line of the history log, describing a finished job
+#[derive(Debug, Serialize)]
+pub struct HistoryEntry {
+    pub input: String,
+    pub output: String,
+    /// in seconds, like job specs
+    pub start: f64,
+    pub dur: f64,
+
+    pub use_video: bool,
+    pub use_audio: bool,
+    pub use_subs: bool,
+    pub use_extra_streams: bool,
+
+    pub video_codec: String,
+
+    pub success: bool,
+    pub status: String,
+}
+
+impl HistoryEntry {
+    pub fn new(media: &Media, result: &Result<String, String>) -> Self {
+        Self {
+            input: media.input.clone(),
+            output: media.output.clone(),
+            start: secs(media.start),
+            dur: secs(media.dur),
+
+            use_video: media.use_video,
+            use_audio: media.use_audio,
+            use_subs: media.use_subs,
+            use_extra_streams: media.use_extra_streams,
+
+            video_codec: media.video_codec.to_string(),
+
+            success: result.is_ok(),
+            status: match result {
+                Ok(status) | Err(status) => status.clone(),
+            },
+        }
+    }
+}
+
+/// appends an entry to the history log, trimming the log if it is over the size cap
+pub fn append_history(entry: &HistoryEntry) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{line}")?;
+
+    if file.metadata()?.len() > HISTORY_SIZE_CAP {
+        let contents = fs::read_to_string(&path)?;
+        let lines: Vec<&str> = contents.lines().collect();
+
+        let mut trimmed = lines[lines.len() / 2..].join("\n");
+        trimmed.push('\n');
+        fs::write(&path, trimmed)?;
+    }
+
+    Ok(())
+}
+
+/// opens a path with the platform's default program
+pub async fn open_path(path: PathBuf) -> Result<(), String> {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+
+    match Command::new(program).arg(&path).status().await {
+        Err(e) => Err(format!("failed to run {program}: {e}")),
+        // explorer exits with 1 even when it opened the path, so only failing to start it counts
+        Ok(_) if cfg!(target_os = "windows") => Ok(()),
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!(
+            "{program} returned {status} while opening '{}'",
+            path.display()
+        )),
+    }
+}
+
+/// runs the post-export command for an output, with `{output}` replaced by its path.
+/// the arguments are passed on their own instead of through a shell, so no path can break them apart
+pub async fn run_post_export(template: String, output: PathBuf) -> Result<(), String> {
+    let args = split_args(&template).map_err(|e| format!("invalid post-export command: {e}"))?;
+    let output = output.to_string_lossy();
+    let mut args = args.iter().map(|arg| arg.replace("{output}", &output));
+    let Some(program) = args.next() else {
+        return Ok(());
+    };
+
+    log!("running {template} for {output}");
+    match Command::new(&program).args(args).status().await {
+        Err(e) => Err(format!("failed to run {program}: {e}")),
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{program} returned {status} for '{output}'")),
+    }
+}
+
+/// shows a file in the platform's file manager, selecting it where possible
+pub async fn reveal_path(path: PathBuf) -> Result<(), String> {
+    // a typed input makes a relative output, which neither the uri nor the folder would find
+    let path = std::path::absolute(&path).unwrap_or(path);
+    let revealed = if cfg!(target_os = "macos") {
+        Command::new("open")
+            .arg("-R")
+            .arg(&path)
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
+    } else if cfg!(target_os = "windows") {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(&path);
+        // explorer exits with 1 even when it selected the file, so only failing to start it counts
+        Command::new("explorer").arg(select).status().await.is_ok()
+    } else {
+        // most linux file managers implement the freedesktop interface for selecting a file
+        let uri = format!("array:string:{}", file_uri(&path));
+        Command::new("dbus-send")
+            .args([
+                "--session",
+                "--dest=org.freedesktop.FileManager1",
+                "--type=method_call",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+            ])
+            .arg(uri)
+            .arg("string:")
+            .status()
+            .await
+            .is_ok_and(|status| status.success())
+    };
+
+    if revealed {
+        return Ok(());
+    }
+    // fall back to opening the containing folder
+    match path.parent() {
+        Some(dir) => open_path(dir.to_path_buf()).await,
+        None => Err(format!("'{}' has no parent folder", path.display())),
+    }
+}
+
+/// a `file://` uri for an absolute path, with everything but unreserved characters and separators percent-encoded
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_encoded_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modify_path_keeps_the_extension() {
+        let path = smol::block_on(modify_path(PathBuf::from("/videos/clip.mp4")));
+        assert_eq!(path, PathBuf::from("/videos/clip_edited.mp4"));
+    }
+
+    #[test]
+    fn modify_path_doesnt_double_the_suffix() {
+        let modify = |path: &str| smol::block_on(modify_path(PathBuf::from(path)));
+        assert_eq!(modify("clip_edited.mkv"), PathBuf::from("clip_edited.mkv"));
+        assert_eq!(
+            modify("clip_edited_2.mkv"),
+            PathBuf::from("clip_edited.mkv")
+        );
+        // only the suffix counts, not a name that happens to contain it
+        assert_eq!(modify("_edited.mkv"), PathBuf::from("_edited_edited.mkv"));
+        assert_eq!(modify("clip_2.mkv"), PathBuf::from("clip_2_edited.mkv"));
+    }
+
+    #[test]
+    fn file_uris_are_percent_encoded() {
+        assert_eq!(
+            file_uri(Path::new("/videos/my clip #2 (100%)/été.mp4")),
+            "file:///videos/my%20clip%20%232%20%28100%25%29/%C3%A9t%C3%A9.mp4"
+        );
+    }
+
+    #[test]
+    fn versioned_path_skips_taken_numbers() {
+        let path = versioned_path(Path::new("clip.mkv"), |path| {
+            path == Path::new("clip_1.mkv") || path == Path::new("clip_2.mkv")
+        });
+        assert_eq!(path, PathBuf::from("clip_3.mkv"));
+    }
+}
@@ -0,0 +1,34 @@
+//! Trims media with ffmpeg, either through the media-tweak GUI or from other Rust tools.
+//!
+//! The trimming itself lives in [`Media`], which can be built without the GUI:
+//!
+//! ```no_run
+//! # smol::block_on(async {
+//! let media = media_tweak::Media::builder("input.mkv", "output.mkv")
+//!     .start(10.0)
+//!     .end(20.0)
+//!     .build()?;
+//!
+//! media
+//!     .run(|progress| println!("{} seconds done", progress.out_time))
+//!     .await?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! # })
+//! # .unwrap();
+//! ```
+//!
+//! The file dialogs and the in-app console are only needed by the GUI,
+//! so tools embedding this can leave them out with `default-features = false`.
+
+pub mod config;
+/// the native dialogs the GUI asks through
+#[cfg(feature = "gui")]
+pub mod dialog;
+pub mod fs;
+pub mod log;
+pub mod media;
+
+pub use media::{
+    Compare, CompareLayout, CreateError, Crop, Finished, Media, MediaBuilder, Mp4Layout,
+    OutputPolicy, PixelFormat, Progress, Split, SubtitleCodec, VideoCodec,
+};
@@ -0,0 +1,38 @@
+use std::sync::OnceLock;
+
+#[cfg(feature = "gui")]
+use iced::Subscription;
+use smol::channel::{self, Receiver, Sender};
+
+/// how many lines the console keeps before dropping the oldest
+pub const MAX_LINES: usize = 500;
+
+static CHANNEL: OnceLock<(Sender<String>, Receiver<String>)> = OnceLock::new();
+
+fn channel() -> &'static (Sender<String>, Receiver<String>) {
+    CHANNEL.get_or_init(channel::unbounded)
+}
+
+/// prints a line to stderr and queues it for the in-app console
+pub fn line(message: String) {
+    eprintln!("{message}");
+    // the receiver is kept in the static, so the channel can't be closed
+    let _ = channel().0.try_send(message);
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($($arg:tt)*) => {
+        $crate::log::line(format!($($arg)*))
+    };
+}
+/// formats and logs a line, like `eprintln!`
+#[doc(inline)]
+pub use crate::__log as log;
+
+/// yields every logged line
+#[cfg(feature = "gui")]
+pub fn subscription() -> Subscription<String> {
+    Subscription::run(|| channel().1.clone())
+}
@@ -1,73 +1,271 @@
 use std::{
+    collections::VecDeque,
     env,
+    ops::RangeInclusive,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 
 use ffmpeg_next as ffmpeg;
 
 use iced::{
-    Color, Element, Event, Length, Subscription, Task, Theme,
+    Color, Element, Event, Font, Length, Subscription, Task, Theme,
     alignment::{Horizontal, Vertical},
     color, event,
+    futures::{SinkExt, channel::mpsc},
     keyboard::{self, Key, key},
+    mouse,
     task::{self},
     widget::{
-        Image, button, checkbox, column, image,
+        Image, button, center, checkbox, column, container, image, mouse_area, opaque,
         operation::{self, focus_next},
-        row, slider, text, text_input,
+        pick_list, progress_bar, radio, row, scrollable, slider, space, stack, text, text_input,
+        tooltip,
     },
     window,
 };
 
-mod fs;
-use fs::*;
-
-mod media;
-use media::*;
+use media_tweak::{
+    config::*,
+    dialog::*,
+    fs::*,
+    log::{self, log},
+    media::*,
+};
 
 #[derive(Debug, Clone)]
 enum Message {
     InputChange(String),
+    /// typing in the input field paused
+    InputSettled,
+    InputArgsChange(String),
+    InputArgsSubmitted,
     OutputChange(String, bool),
 
     PickInput,
+    PickCompareInput,
+    CompareInputPicked(Option<PathBuf>),
+    CompareInputChange(String),
+    CompareLayoutChange(CompareLayout),
+    PickWatermark,
+    WatermarkPicked(Option<PathBuf>),
+    WatermarkCornerChange(Corner),
+    WatermarkOpacityChange(f64),
+    RemoveWatermark,
+    PadLengthChange(String),
+    PadPlacementChange(PadPlacement),
+    PadFillChange(PadFill),
     PickOutput,
     InputPicked(Option<PathBuf>),
     OutputPicked(Option<PathBuf>),
 
-    StartChange(f64),
-    EndChange(f64),
-    EagerStartChange(f64),
-    EagerEndChange(f64),
+    /// in microseconds, like every time in the range
+    StartChange(i64),
+    EndChange(i64),
+    EagerStartChange(i64),
+    EagerEndChange(i64),
+    /// the mouse wheel turned over a time slider
+    StartScrolled(mouse::ScrollDelta),
+    EndScrolled(mouse::ScrollDelta),
+    ScrollStepChange(String),
+    /// for boundaries that were set in the wrong order
+    SwapBoundaries,
+    /// sets both boundaries to a chapter's start and end
+    ChapterPicked(ChapterInfo),
+    RangeChange(String),
+    /// sets both boundaries from the range field
+    RangeSubmitted,
+    /// a pasted range is applied right away
+    RangePasted(String),
+    ToggleDropChapters,
 
     ToggleVideo,
     ToggleAudio,
     ToggleSubs,
     ToggleExtraStreams,
+    AudioOffsetChange(f64),
+    SilenceThresholdChange(String),
+    SilenceDurationChange(String),
+    DetectSilence,
+    DetectedSilence(Result<(i64, i64), String>),
+    StreamPresetChange(StreamPreset),
+    ToggleDefaultStream(StreamKind),
+
+    VideoCodecChange(VideoCodec),
+    PixelFormatChange(PixelFormat),
+    FormatChange(Container),
+    ToggleCrf,
+    CrfChange(u8),
+    SubtitleCodecChange(SubtitleCodec),
+
+    CropChange(String),
+    KeyframeIntervalChange(String),
+    ToggleForceKeyframes,
+    DetectCrop,
+    DetectedCrop(Result<Crop, String>),
+    ApplyCrop,
+
+    SplitPartsChange(String),
+    SplitMaxChange(String),
+    ToggleCopyTimestamps,
+    ToggleFixTimestamps,
+    /// the last run hit timestamps going backwards, which `fix_timestamps` can retry without
+    SuggestTimestampFix,
+    RetryWithTimestampFix,
+    ToggleShortest,
+    AddExtraOutput,
+    RemoveExtraOutput(usize),
+    ExtraOutputChange(usize, String),
+    ExtraOutputCodecChange(usize, VideoCodec),
+    OutputPolicyChange(OutputPolicy),
+    ToggleLowPriority,
+    Mp4LayoutChange(Mp4Layout),
+
+    ToggleFrameMode,
+    TogglePercentMode,
+    ToggleExactPreview,
+    ToggleProxy,
+    /// how many packets a slow preview decode has scanned so far
+    StartPreviewProgress(usize),
+    EndPreviewProgress(usize),
+    PreviewOffsetChange(f64),
+    LoadedProxy(Result<PathBuf, String>),
+    LoadedKeyframes(Result<Vec<i64>, String>),
+    /// the input that was probed, with its streams filled in
+    InputProbed(String, Result<(Media, InputInfo), String>),
+    ToggleHistogram,
+    ToggleDecodeTime,
+    ToggleFrameTimes,
+    ToggleScrub,
+    ScrubChange(f64),
+    /// also carries the input it was decoded for
+    LoadedScrubPreview(String, PreviewResult),
+    PreviewSizeChange(PreviewSize),
+    ToggleHistory,
+    OpenHistory,
+    ToggleKeepOpen,
+    ToggleVerifyOutput,
+    ToggleKeepMtime,
+    ToggleCreateOutputDirs,
+    LoadPreviousOutput,
+    DismissPreviousOutput,
+    ToggleOfferLastOutput,
+    ToggleAdvancedView,
+    PostExportCommandChange(String),
+    EncoderFallbacksChange(String),
+    /// typing in a settings field paused
+    SaveSettings,
+    RevealOutput,
+    Opened(Result<(), String>),
 
     Submitted,
 
     Update,
 
-    LoadedStartPreview(Result<(image::Handle, u64), PreviewError>),
-    LoadedEndPreview(Result<(image::Handle, u64), PreviewError>),
+    /// also carries how long decoding took
+    LoadedStartPreview(PreviewResult, Duration),
+    LoadedEndPreview(PreviewResult, Duration),
+    LoadedLoudness(Result<Loudness, String>),
+    /// how far the input can be read, if that's short of its length
+    LoadedReadableLength(String, Option<f64>),
+    /// starts playing the selected range's audio, or stops it if it's playing
+    PlayAudio,
+    AudioPlayed(Result<(), String>),
+    LoadedWaveform(Result<image::Handle, String>),
+
+    /// also carries whether a widget, like a focused text field, already handled it
+    Event(Event, event::Status),
+
+    Logged(String),
+    ToggleConsole,
 
-    Event(Event),
+    OpenPalette,
+    PaletteChange(String),
+    /// runs the nth action that matches the query
+    PaletteRun(usize),
+
+    SheetRowsChange(String),
+    SheetColumnsChange(String),
+    SheetWidthChange(String),
+    CreateSheet,
+    SheetFinished(Result<PathBuf, String>),
+    StillsEveryChange(String),
+    StillsMaxChange(String),
+    StillsFormatChange(StillFormat),
+    ExportStills,
+    StillsFinished(Result<PathBuf, String>),
+
+    SampleLengthChange(String),
+    CreateSample,
+    SampleFinished(Result<PathBuf, String>),
+
+    MetadataTitleChange(String),
+    MetadataRotationChange(u32),
+    EditMetadata,
+    MetadataConfirmed(bool),
+    MetadataEdited(Result<(), String>),
+
+    SaveAttachment(usize),
+    AttachmentPathPicked(usize, Option<PathBuf>),
+    AttachmentSaved(Result<PathBuf, String>),
+
+    SaveJob,
+    JobSavePathPicked(Option<PathBuf>),
+    LoadJob,
+    JobPicked(Option<PathBuf>),
+    /// queues every setting but the range and the outputs for other inputs
+    QueueSettingsForFiles,
+    QueueSettingsFilesPicked(Vec<PathBuf>),
+    /// another input was probed to queue the settings for
+    SettingsJobReady(Result<Media, String>),
+
+    /// queues the current settings, to run after the jobs already queued
+    AddToQueue,
+    RemoveQueuedJob(usize),
+    /// a pending job's handle was pressed, so it follows the mouse until it's released
+    DragQueuedJob(usize),
+    /// the mouse entered a job's row, where the dragged job moves to
+    DragOverQueuedJob(usize),
+    /// shows what the queue will do, to run it from or cancel
+    RunQueue,
+    ConfirmRunQueue,
+    CancelRunQueue,
+    StopQueue,
+    QueueProgress(Progress),
+    /// the running job finished, carrying the status to show on success
+    QueuedJobFinished(Result<String, String>),
+    RestoreQueue,
+    DiscardQueue,
 
     Instantiate,
-    InstantiateFinished(Result<(), String>),
+    CancelInstantiate,
+    Progress(Progress),
+    /// carries the status to show on success
+    InstantiateFinished(Result<String, String>),
 }
 
 #[derive(Debug, Default)]
 struct State {
     media: Media,
+    settings: Settings,
+    settings_save_task_handle: Option<task::Handle>,
+    /// the last session's output, offered as the input until dismissed
+    previous_output: Option<PathBuf>,
+
+    available_codecs: Vec<VideoCodec>,
 
     input_changed: bool,
     input_exists: bool,
+    input_debounce_task_handle: Option<task::Handle>,
+    probe_task_handle: Option<task::Handle>,
+    /// a loaded job spec, applied once its input is probed
+    pending_job: Option<Media>,
 
-    input_length: f64,
+    /// in microseconds, like the start and end
+    input_length: i64,
+    input_info: InputInfo,
 
-    end: f64,
+    end: i64,
     number_changed: bool,
 
     last_start_preview: Preview,
@@ -79,12 +277,173 @@ struct State {
     start_preview: Option<image::Handle>,
     end_preview: Option<image::Handle>,
 
+    start_histogram: Option<Histogram>,
+    end_histogram: Option<Histogram>,
+    show_histogram: bool,
+
+    start_decode_time: Option<Duration>,
+    end_decode_time: Option<Duration>,
+    show_decode_time: bool,
+
+    /// a separate slider with its own preview, which follows it while it's dragged
+    show_scrub: bool,
+    scrub_position: f64,
+    current_scrub_preview: Option<image::Handle>,
+    last_scrub_preview_hash: u64,
+    /// only one scrub preview is decoded at a time, so dragging doesn't queue up decodes
+    scrub_decoding: bool,
+    /// the slider moved while decoding, so the latest position is decoded next
+    scrub_pending: bool,
+
+    start_frame_time: Option<FrameTime>,
+    end_frame_time: Option<FrameTime>,
+    /// show and log the timestamp of each previewed frame
+    show_frame_times: bool,
+
+    /// seconds after each boundary to show in its preview, negative to look before it
+    preview_offset: f64,
+
+    /// decode previews from a low resolution copy of the input, for inputs too big to scrub
+    use_proxy: bool,
+    /// created as soon as the encode starts, so the file is cleaned up even if it never finishes
+    proxy: Option<TempFile>,
+    proxy_ready: bool,
+    proxy_task_handle: Option<task::Handle>,
+
+    /// the input's keyframe times around the boundaries, shown on the sliders when copying
+    keyframes: Vec<i64>,
+    /// the start and end the keyframes were last looked for around
+    keyframes_around: Option<(i64, i64)>,
+    keyframes_task_handle: Option<task::Handle>,
+
     start_preview_task_handle: Option<task::Handle>,
+    start_preview_scanned: Option<usize>,
+    end_preview_scanned: Option<usize>,
     end_preview_task_handle: Option<task::Handle>,
 
+    last_waveform_probe: WaveformProbe,
+    waveform: Option<image::Handle>,
+    waveform_task_handle: Option<task::Handle>,
+
+    last_loudness_probe: LoudnessProbe,
+    loudness: Option<Loudness>,
+    loudness_task_handle: Option<task::Handle>,
+
+    /// present while the selected range's audio is playing
+    audio_playback_task_handle: Option<task::Handle>,
+
+    /// the comparison is only used while there is a second input
+    compare_input: String,
+    compare_layout: CompareLayout,
+
+    /// what a metadata-only edit changes, where an empty title is kept
+    metadata_title: String,
+    metadata_rotation: Option<u32>,
+    editing_metadata: bool,
+
+    /// both boundaries at once, like a subtitle's timing line
+    range_field: String,
+    range_error: Option<String>,
+
+    /// kept separately so the field can hold partial input
+    input_args_field: String,
+    input_args_error: Option<String>,
+    post_export_error: Option<String>,
+    /// kept separately so the field can hold partial input
+    encoder_fallbacks_field: String,
+    encoder_fallbacks_error: Option<String>,
+    /// the scroll step as typed, which keeps the last valid one while it doesn't parse
+    scroll_step_field: String,
+
+    /// kept separately so the field can hold partial input
+    crop_field: String,
+    suggested_crop: Option<Crop>,
+    keyframe_field: String,
+    pad_field: String,
+    pad_placement: PadPlacement,
+    pad_fill: PadFill,
+    /// the container picked for the output, which generated outputs follow instead of the input's
+    container: Option<Container>,
+    detecting_crop: bool,
+
+    /// only the threshold and minimum duration are kept between detections
+    silence: SilenceProbe,
+    /// the minimum silence duration as typed, which keeps the last valid one while it doesn't parse
+    silence_duration_field: String,
+    detecting_silence: bool,
+
+    /// show the boundaries as frame numbers, when the frame rate is known
+    frame_mode: bool,
+    /// show the boundaries as percentages of the input's duration, instead of frame numbers
+    percent_mode: bool,
+
+    exact_preview: bool,
+    preview_size: PreviewSize,
+    log_history: bool,
+
     output_is_generated: bool,
     output_folder_exists: bool,
 
+    keep_open: bool,
+    verify_output: bool,
+    keep_mtime: bool,
+    last_output: Option<PathBuf>,
+
+    /// an export is running, which works from a copy of the settings taken when it started,
+    /// so they can't be edited until it's done
+    processing: bool,
+    instantiate_task_handle: Option<task::Handle>,
+    /// offer a retry with the timestamps fixed, after they broke the last run
+    suggest_timestamp_fix: bool,
+
+    /// the latest speed reports, for smoothing the ETA
+    speed_samples: VecDeque<f64>,
+    eta: Option<f64>,
+    /// how much of the output is written, from 0 to 1
+    progress: f64,
+    /// ffmpeg reported progress, so the outputs it was writing were already truncated
+    output_started: bool,
+
+    console: VecDeque<String>,
+    show_console: bool,
+
+    sheet: ContactSheet,
+    creating_sheet: bool,
+
+    stills: StillExport,
+    exporting_stills: bool,
+
+    /// the command palette's query, while it's open
+    palette: Option<String>,
+
+    /// seconds of output to encode from the start, to check the settings on
+    sample_length: f64,
+    creating_sample: bool,
+    /// the last sample, which is deleted when replaced or when the program exits
+    sample: Option<TempFile>,
+
+    /// jobs that run one after another, saved whenever they change
+    queue: Queue,
+    /// the queue the last session left, offered until it's restored or discarded
+    restorable_queue: Option<Queue>,
+    /// present while a queued job runs, which works from its own copy of the settings
+    queue_task_handle: Option<task::Handle>,
+    /// how much of the running job's output is written, from 0 to 1
+    queue_progress: f64,
+    /// seconds of output the jobs already done in this run made up,
+    /// so the whole run's progress weighs each job by its duration
+    queue_done: f64,
+    /// the latest speed reports of the queued jobs, for smoothing the queue's ETA
+    queue_speed_samples: VecDeque<f64>,
+    queue_eta: Option<f64>,
+    /// the index of the job being dragged to reorder the queue
+    dragged_job: Option<usize>,
+    /// the summary of every job about to run is shown, to start the queue from or cancel
+    confirming_queue: bool,
+
+    /// the held modifiers, which make scrolling over a slider finer or coarser
+    modifiers: keyboard::Modifiers,
+
     error: String,
     status: String,
 }
@@ -93,34 +452,131 @@ impl State {
     fn new() -> (Self, Task<Message>) {
         ffmpeg::init().unwrap();
 
-        let state = State::default();
+        let settings = Settings::load();
+        let previous_output = settings
+            .last_output
+            .clone()
+            .filter(|path| settings.offer_last_output && path.exists());
+
+        let encoder_fallbacks_field = settings
+            .encoder_fallbacks
+            .iter()
+            .filter_map(VideoCodec::encoder)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut state = State {
+            media: Media {
+                output_policy: settings.output_policy,
+                ..Default::default()
+            },
+            last_output: previous_output.clone(),
+            encoder_fallbacks_field,
+            scroll_step_field: settings.scroll_step.to_string(),
+            settings,
+            restorable_queue: Some(Queue::load()).filter(|queue| !queue.jobs.is_empty()),
+            available_codecs: VideoCodec::available(),
+            verify_output: true,
+            sample_length: 5.0,
+            silence_duration_field: SilenceProbe::default().min_duration.to_string(),
+            ..Default::default()
+        };
 
         // Uses the first argument as the input file path,
         // and creates the output file path from it
         let mut args = env::args();
         if let Some(str) = args.nth(1) {
+            // `--spec job.json` loads a job spec instead of an input
+            if str == "--spec" {
+                let path = args.next().map(PathBuf::from);
+                return (state, Task::done(Message::JobPicked(path)));
+            }
+
             (
                 state,
                 Task::done(Message::InputChange(str)).chain(Task::done(Message::Update)),
             )
         } else {
+            // only offered when there is no input yet
+            state.previous_output = previous_output;
             (state, Task::none())
         }
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        // the palette and the hotkeys dispatch these too, without the widgets' checks
+        if !self.allows(&message) {
+            return Task::none();
+        }
+
         match message {
             Message::InputChange(str) => {
                 self.media.input = str;
                 self.input_changed = true;
-                if let Ok(exists) = Path::new(&self.media.input).try_exists().inspect_err(|e| {
-                    eprintln!(
-                        "failed to check if input '{}' exists: {e}",
-                        self.media.input
-                    )
-                }) {
+                self.previous_output = None;
+                // a URL can't be checked without opening it, so that is left to probing
+                if is_network_url(&self.media.input) {
+                    self.input_exists = true;
+                } else if let Ok(exists) =
+                    Path::new(&self.media.input).try_exists().inspect_err(|e| {
+                        log!(
+                            "failed to check if input '{}' exists: {e}",
+                            self.media.input
+                        )
+                    })
+                {
                     self.input_exists = exists;
                 }
+
+                // a typed path is only opened once typing pauses
+                let (task, handle) = Task::perform(smol::Timer::after(INPUT_DEBOUNCE), |_| {
+                    Message::InputSettled
+                })
+                .abortable();
+                if let Some(extra_handle) = &self.input_debounce_task_handle {
+                    extra_handle.abort();
+                }
+                self.input_debounce_task_handle = Some(handle);
+
+                return task;
+            }
+            Message::InputSettled => return self.check_inputs(),
+            Message::InputProbed(input, result) => {
+                self.probe_task_handle = None;
+                // a probe that was overtaken by another input can still finish before it's aborted
+                if input != self.media.input {
+                    return Task::none();
+                }
+                let job = self.pending_job.take();
+
+                match result {
+                    Ok((probed, info)) => {
+                        self.apply_probe(probed, info);
+                        return match job {
+                            Some(job) => self.finish_job(job),
+                            None => self.update_from_input(),
+                        };
+                    }
+                    Err(e) if job.is_some() => {
+                        self.error = format!("failed to inspect input media '{input}': {e}")
+                    }
+                    Err(e) => log!("failed to inspect input media '{input}': {e}"),
+                }
+            }
+            Message::InputArgsChange(str) => {
+                match split_args(&str) {
+                    Ok(args) => {
+                        self.media.input_args = args;
+                        self.input_args_error = None;
+                    }
+                    Err(e) => self.input_args_error = Some(e),
+                }
+                self.input_args_field = str;
+            }
+            // the input is probed again with the new options
+            Message::InputArgsSubmitted => {
+                self.input_changed = true;
+                return self.check_inputs();
             }
             Message::OutputChange(str, is_generated) => {
                 self.media.output = str;
@@ -128,7 +584,7 @@ impl State {
                 if let Some(path) = Path::new(&self.media.output).parent()
                     && let Ok(exists) = path
                         .try_exists()
-                        .inspect_err(|e| eprintln!("failed to check if input exists: {e}"))
+                        .inspect_err(|e| log!("failed to check if input exists: {e}"))
                 {
                     self.output_folder_exists = exists;
                 }
@@ -145,19 +601,140 @@ impl State {
             }
 
             Message::EagerStartChange(val) => {
-                self.media.start = val;
+                self.media.start = self.capped_start(val);
                 self.number_changed = true;
                 self.media.dur = self.end - self.media.start;
                 return self.check_inputs();
             }
             Message::EagerEndChange(val) => {
-                self.end = val;
+                // only typed ends count back from the end of the input
+                self.end = self.capped_end(val.max(0));
+                self.number_changed = true;
+                self.media.dur = self.end - self.media.start;
+                return self.check_inputs();
+            }
+
+            Message::StartScrolled(delta) => {
+                let start = self.media.start + self.scroll_offset(delta);
+                return Task::done(Message::EagerStartChange(start));
+            }
+            Message::EndScrolled(delta) => {
+                // scrolling starts from the resolved end, like the slider shows it
+                let (_, end) = self.clamped_range();
+                return Task::done(Message::EagerEndChange(end + self.scroll_offset(delta)));
+            }
+            Message::ScrollStepChange(str) => {
+                let step = parse_scroll_step(&str);
+                self.scroll_step_field = str;
+                if let Some(step) = step {
+                    self.settings.scroll_step = step;
+                    return self.save_settings_later();
+                }
+            }
+
+            Message::SwapBoundaries => {
+                // resolve a negative end first, so it isn't swapped in as a start.
+                // clamping would undo the wrong order before it can be swapped
+                if self.end < 0 {
+                    self.end = (self.end + self.input_length).max(0);
+                }
+                std::mem::swap(&mut self.media.start, &mut self.end);
+                self.number_changed = true;
+                self.media.dur = self.end - self.media.start;
+                return self.check_inputs();
+            }
+            // extracting a chapter is usually about speed, so the video is copied, saying so if that changes the codec
+            Message::ChapterPicked(chapter) => {
+                self.media.start = chapter.start;
+                self.end = chapter.end;
+                if self.media.video_codec != VideoCodec::Copy {
+                    self.media.video_codec = VideoCodec::Copy;
+                    self.status = format!("Switched the video to copy to extract {chapter}");
+                }
                 self.number_changed = true;
                 self.media.dur = self.end - self.media.start;
                 return self.check_inputs();
             }
+            Message::RangeChange(str) => {
+                self.range_error = None;
+                self.range_field = str;
+            }
+            Message::RangePasted(str) => {
+                self.range_field = str;
+                return Task::done(Message::RangeSubmitted);
+            }
+            Message::RangeSubmitted => match parse_range(&self.range_field) {
+                Ok((start, end)) => {
+                    self.range_error = None;
+                    self.media.start = micros(start);
+                    self.end = micros(end);
+                    self.number_changed = true;
+                    self.media.dur = self.end - self.media.start;
+                    return self.check_inputs();
+                }
+                Err(e) => self.range_error = Some(e),
+            },
+            Message::ToggleDropChapters => self.media.drop_chapters = !self.media.drop_chapters,
 
             Message::PickInput => return Task::perform(pick_file(), Message::InputPicked),
+            Message::PickCompareInput => {
+                return Task::perform(pick_file(), Message::CompareInputPicked);
+            }
+            Message::CompareInputPicked(opt) => {
+                if let Some(path) = opt
+                    && let Some(str) = path.to_str()
+                {
+                    return Task::done(Message::CompareInputChange(str.to_owned()));
+                }
+            }
+            Message::CompareInputChange(str) => {
+                self.compare_input = str;
+                self.update_compare();
+            }
+            Message::CompareLayoutChange(layout) => {
+                self.compare_layout = layout;
+                self.update_compare();
+            }
+            Message::PickWatermark => return Task::perform(pick_file(), Message::WatermarkPicked),
+            Message::WatermarkPicked(opt) => {
+                if let Some(path) = opt {
+                    let image = path.to_string_lossy().into_owned();
+                    // picking another image keeps where and how it's drawn
+                    match &mut self.media.watermark {
+                        Some(watermark) => watermark.image = image,
+                        None => {
+                            self.media.watermark = Some(Watermark {
+                                image,
+                                corner: Corner::default(),
+                                opacity: 1.0,
+                            })
+                        }
+                    }
+                }
+            }
+            Message::WatermarkCornerChange(corner) => {
+                if let Some(watermark) = &mut self.media.watermark {
+                    watermark.corner = corner;
+                }
+            }
+            Message::WatermarkOpacityChange(opacity) => {
+                if let Some(watermark) = &mut self.media.watermark {
+                    watermark.opacity = opacity;
+                }
+            }
+            Message::RemoveWatermark => self.media.watermark = None,
+            Message::PadLengthChange(str) => {
+                self.pad_field = str;
+                self.update_padding();
+            }
+            Message::PadPlacementChange(placement) => {
+                self.pad_placement = placement;
+                self.update_padding();
+            }
+            Message::PadFillChange(fill) => {
+                self.pad_fill = fill;
+                self.update_padding();
+            }
             Message::PickOutput => return Task::perform(pick_folder(), Message::OutputPicked),
             Message::InputPicked(opt) => {
                 if let Some(path) = opt
@@ -186,146 +763,1219 @@ impl State {
             Message::Update => return self.check_inputs(),
 
             Message::ToggleVideo => self.media.use_video = !self.media.use_video,
-            Message::ToggleAudio => self.media.use_audio = !self.media.use_audio,
+            Message::ToggleAudio => {
+                self.media.use_audio = !self.media.use_audio;
+                return self.measure_loudness();
+            }
             Message::ToggleSubs => self.media.use_subs = !self.media.use_subs,
             Message::ToggleExtraStreams => {
                 self.media.use_extra_streams = !self.media.use_extra_streams
             }
 
-            Message::LoadedStartPreview(Ok((handle, hash))) => {
-                self.last_start_preview_hash = hash;
-                self.start_preview = Some(handle)
-            }
-            Message::LoadedEndPreview(Ok((handle, hash))) => {
-                self.last_end_preview_hash = hash;
-                self.end_preview = Some(handle)
-            }
-            Message::LoadedStartPreview(Err(e)) | Message::LoadedEndPreview(Err(e)) => {
-                if e != PreviewError::SameHash {
-                    eprintln!("{e}")
+            Message::SubtitleCodecChange(codec) => self.media.sub_codec = codec,
+            Message::AudioOffsetChange(offset) => self.media.audio_offset = offset,
+
+            Message::ToggleDefaultStream(stream) => {
+                self.settings.default_streams.toggle(stream);
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
                 }
             }
 
-            Message::Event(event) => {
-                if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event {
-                    match key.as_ref() {
-                        // input field cycling
-                        Key::Named(key::Named::Tab) => {
-                            if modifiers.shift() {
-                                return operation::focus_previous();
-                            } else {
-                                return operation::focus_next();
-                            }
-                        }
+            Message::StreamPresetChange(preset) => {
+                preset.apply(&mut self.media, &self.input_info);
+                return Task::batch([self.create_preview_images(), self.measure_loudness()]);
+            }
 
-                        Key::Named(key::Named::ArrowRight) | Key::Character("l") => {
-                            return if modifiers.shift() {
-                                Task::done(Message::EagerEndChange(self.end + 5.0))
-                            } else {
-                                Task::done(Message::EagerStartChange(self.media.start + 5.0))
-                            };
-                        }
-                        Key::Named(key::Named::ArrowLeft) | Key::Character("h") => {
-                            return if modifiers.shift() {
-                                Task::done(Message::EagerEndChange(self.end - 5.0))
-                            } else {
-                                Task::done(Message::EagerStartChange(self.media.start - 5.0))
-                            };
+            Message::VideoCodecChange(codec) => {
+                let left = self.media.video_codec.container();
+                self.media.video_codec = codec;
+                // a factor the new encoder doesn't go up to is brought down to its highest
+                if let (Some(crf), Some(max)) = (self.media.crf, codec.max_crf()) {
+                    self.media.crf = Some(crf.min(max));
+                }
+                match (codec.container(), left) {
+                    (Some(container), _) => self.apply_format(Some(container)),
+                    // the container was only there for the codec
+                    (None, Some(_)) => {
+                        if self
+                            .container
+                            .is_some_and(|container| Some(container) == left)
+                        {
+                            self.container = None;
                         }
+                        self.apply_format(self.container);
+                    }
+                    (None, None) => (),
+                }
+            }
+            Message::FormatChange(container) => {
+                self.container = Some(container);
+                self.apply_format(Some(container));
+            }
+            Message::PixelFormatChange(pix_fmt) => self.media.pix_fmt = pix_fmt,
+            Message::ToggleCrf => {
+                self.media.crf = match self.media.crf {
+                    Some(_) => None,
+                    None => Some(DEFAULT_CRF),
+                }
+            }
+            Message::CrfChange(crf) => self.media.crf = Some(crf),
 
-                        Key::Named(key::Named::ArrowUp) | Key::Character("k") => {
-                            return if modifiers.shift() {
-                                Task::done(Message::EagerEndChange(self.end + 10.0))
-                            } else {
-                                Task::done(Message::EagerStartChange(self.media.start + 10.0))
-                            };
-                        }
-                        Key::Named(key::Named::ArrowDown) | Key::Character("j") => {
-                            return if modifiers.shift() {
-                                Task::done(Message::EagerEndChange(self.end - 10.0))
-                            } else {
-                                Task::done(Message::EagerStartChange(self.media.start - 10.0))
-                            };
-                        }
+            Message::SplitPartsChange(str) => {
+                self.media.split = match str.parse() {
+                    Ok(parts) => Split::Parts(parts),
+                    Err(_) => Split::Off,
+                }
+            }
+            Message::SplitMaxChange(str) => {
+                self.media.split = match str.parse() {
+                    Ok(max) => Split::MaxDuration(max),
+                    Err(_) => Split::Off,
+                }
+            }
 
-                        Key::Character("v") => return Task::done(Message::ToggleVideo),
-                        Key::Character("a") => return Task::done(Message::ToggleAudio),
-                        Key::Character("s") => return Task::done(Message::ToggleSubs),
-                        Key::Character("e") => return Task::done(Message::ToggleExtraStreams),
+            Message::CropChange(str) => {
+                self.media.crop = str.parse().ok();
+                self.crop_field = str;
+            }
+            Message::KeyframeIntervalChange(str) => {
+                self.media.keyframe_interval = str.parse().ok();
+                self.keyframe_field = str;
+            }
+            Message::ToggleForceKeyframes => {
+                self.media.force_keyframes = !self.media.force_keyframes
+            }
+            Message::DetectCrop => {
+                self.detecting_crop = true;
+                let (start, dur) = self.clamped_span();
+                let probe = CropProbe {
+                    input: self.media.input.clone(),
+                    start,
+                    dur,
+                };
+                return Task::perform(probe.detect(), Message::DetectedCrop);
+            }
+            Message::DetectedCrop(result) => {
+                self.detecting_crop = false;
+                match result {
+                    Ok(crop) => self.suggested_crop = Some(crop),
+                    Err(e) => self.error = e,
+                }
+            }
+            Message::ApplyCrop => {
+                if let Some(crop) = self.suggested_crop.take() {
+                    self.media.crop = Some(crop);
+                    self.crop_field = crop.to_string();
+                }
+            }
 
-                        // early-exit hotkeys
-                        Key::Named(key::Named::Escape) | Key::Character("q") => {
-                            return window::latest().and_then(window::close);
-                        }
+            Message::SilenceThresholdChange(str) => {
+                if let Ok(threshold) = str.parse() {
+                    self.silence.threshold = threshold;
+                }
+            }
+            Message::SilenceDurationChange(str) => {
+                if let Some(min_duration) = parse_silence_duration(&str) {
+                    self.silence.min_duration = min_duration;
+                }
+                self.silence_duration_field = str;
+            }
+            Message::DetectSilence => {
+                self.error.clear();
+                self.detecting_silence = true;
+                let (start, dur) = self.clamped_span();
+                let probe = SilenceProbe {
+                    input: self.media.input.clone(),
+                    start,
+                    dur,
+                    ..self.silence.clone()
+                };
+                return Task::perform(probe.detect(), Message::DetectedSilence);
+            }
+            Message::DetectedSilence(result) => {
+                self.detecting_silence = false;
+                match result {
+                    Ok((start, end)) => {
+                        self.media.start = start;
+                        self.end = end;
+                        self.number_changed = true;
+                        self.media.dur = self.end - self.media.start;
+                        return self.check_inputs();
+                    }
+                    Err(e) => self.error = e,
+                }
+            }
 
-                        Key::Named(key::Named::Enter) => {
-                            if modifiers.shift() {
-                                return Task::done(Message::Instantiate);
-                            } else {
-                                return focus_next();
-                            }
-                        }
+            Message::OutputPolicyChange(policy) => {
+                self.media.output_policy = policy;
+                self.settings.output_policy = policy;
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
+                }
+            }
+            Message::Mp4LayoutChange(layout) => self.media.mp4_layout = layout,
+            Message::ToggleLowPriority => self.media.low_priority = !self.media.low_priority,
+            Message::ToggleCopyTimestamps => self.media.copy_ts = !self.media.copy_ts,
+            Message::ToggleFixTimestamps => self.media.fix_timestamps = !self.media.fix_timestamps,
+            Message::SuggestTimestampFix => self.suggest_timestamp_fix = true,
+            Message::RetryWithTimestampFix => {
+                self.media.fix_timestamps = true;
+                return Task::done(Message::Instantiate);
+            }
+            Message::ToggleShortest => self.media.keep_stream_tails = !self.media.keep_stream_tails,
+            Message::AddExtraOutput => {
+                // numbered after the output, skipping the names the other outputs have
+                let taken = self.media.outputs();
+                let output = versioned_path(Path::new(&self.media.output), |path| {
+                    path.exists() || taken.iter().any(|output| output.as_path() == path)
+                });
+                self.media.extra_outputs.push(ExtraOutput {
+                    output: output.to_string_lossy().into_owned(),
+                    video_codec: self.media.video_codec,
+                });
+            }
+            Message::RemoveExtraOutput(i) => {
+                if i < self.media.extra_outputs.len() {
+                    self.media.extra_outputs.remove(i);
+                }
+            }
+            Message::ExtraOutputChange(i, str) => {
+                if let Some(extra) = self.media.extra_outputs.get_mut(i) {
+                    extra.output = str;
+                }
+            }
+            Message::ExtraOutputCodecChange(i, codec) => {
+                if let Some(extra) = self.media.extra_outputs.get_mut(i) {
+                    extra.video_codec = codec;
+                }
+            }
 
-                        _ => {}
+            Message::ToggleExactPreview => {
+                self.exact_preview = !self.exact_preview;
+                return self.create_preview_images();
+            }
+            // the fields only have room for one unit
+            Message::ToggleFrameMode => {
+                self.frame_mode = !self.frame_mode;
+                self.percent_mode &= !self.frame_mode;
+            }
+            Message::TogglePercentMode => {
+                self.percent_mode = !self.percent_mode;
+                self.frame_mode &= !self.percent_mode;
+            }
+            Message::ToggleDecodeTime => self.show_decode_time = !self.show_decode_time,
+            Message::ToggleFrameTimes => self.show_frame_times = !self.show_frame_times,
+            Message::ToggleHistogram => self.show_histogram = !self.show_histogram,
+            Message::PreviewSizeChange(size) => {
+                self.preview_size = size;
+                return self.create_preview_images();
+            }
+            Message::ToggleHistory => self.log_history = !self.log_history,
+            Message::OpenHistory => {
+                if let Some(path) = history_path() {
+                    return Task::perform(open_path(path), Message::Opened);
+                }
+            }
+            Message::ToggleKeepOpen => self.keep_open = !self.keep_open,
+            Message::ToggleVerifyOutput => self.verify_output = !self.verify_output,
+            Message::ToggleKeepMtime => self.keep_mtime = !self.keep_mtime,
+            Message::ToggleCreateOutputDirs => {
+                self.settings.create_output_dirs = !self.settings.create_output_dirs;
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
+                }
+            }
+            Message::LoadPreviousOutput => {
+                if let Some(path) = self.previous_output.take() {
+                    return Task::done(Message::InputChange(path.to_string_lossy().to_string()))
+                        .chain(Task::done(Message::Update));
+                }
+            }
+            Message::DismissPreviousOutput => self.previous_output = None,
+            Message::ToggleAdvancedView => {
+                self.settings.advanced_view = !self.settings.advanced_view;
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
+                }
+            }
+            Message::ToggleOfferLastOutput => {
+                self.settings.offer_last_output = !self.settings.offer_last_output;
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
+                }
+            }
+            Message::PostExportCommandChange(str) => {
+                self.post_export_error = split_args(&str).err();
+                self.settings.post_export_command = str;
+                return self.save_settings_later();
+            }
+            Message::EncoderFallbacksChange(str) => {
+                let parsed = VideoCodec::parse_list(&str);
+                self.encoder_fallbacks_field = str;
+                match parsed {
+                    Ok(codecs) => {
+                        self.encoder_fallbacks_error = None;
+                        self.settings.encoder_fallbacks = codecs;
+                        return self.save_settings_later();
                     }
+                    Err(e) => self.encoder_fallbacks_error = Some(e),
+                }
+            }
+            Message::SaveSettings => {
+                self.settings_save_task_handle = None;
+                if let Err(e) = self.settings.save() {
+                    log!("failed to save the settings: {e}");
+                }
+            }
+            Message::RevealOutput => {
+                if let Some(path) = self.last_output.clone() {
+                    return Task::perform(reveal_path(path), Message::Opened);
+                }
+            }
+            Message::Opened(result) => {
+                if let Err(e) = result {
+                    self.error = e
                 }
             }
 
-            Message::Instantiate => {
-                self.error.clear();
-                self.status = "Loading...".to_string();
-                return self.instantiate();
+            Message::StartPreviewProgress(packets) => self.start_preview_scanned = Some(packets),
+            Message::EndPreviewProgress(packets) => self.end_preview_scanned = Some(packets),
+            Message::LoadedStartPreview(Ok((handle, histogram, hash, frame_time)), took) => {
+                self.start_preview_scanned = None;
+                self.start_decode_time = Some(took);
+                self.start_frame_time = frame_time;
+                self.log_frame_time("start", frame_time);
+                self.last_start_preview_hash = hash;
+                self.start_preview = Some(handle);
+                self.start_histogram = Some(histogram);
             }
-            Message::InstantiateFinished(result) => match result {
-                Ok(()) => {
-                    self.status = "Finished".to_string();
-                    return window::latest().and_then(window::close);
+            Message::LoadedEndPreview(Ok((handle, histogram, hash, frame_time)), took) => {
+                self.end_preview_scanned = None;
+                self.end_decode_time = Some(took);
+                self.end_frame_time = frame_time;
+                self.log_frame_time("end", frame_time);
+                self.last_end_preview_hash = hash;
+                self.end_preview = Some(handle);
+                self.end_histogram = Some(histogram);
+            }
+            Message::ToggleScrub => {
+                self.show_scrub = !self.show_scrub;
+                if self.show_scrub {
+                    return self.decode_scrub_preview();
+                }
+            }
+            Message::ScrubChange(position) => {
+                self.scrub_position = position;
+                return self.decode_scrub_preview();
+            }
+            Message::LoadedScrubPreview(input, result) => {
+                // the decoding for a new input was started after this one was let go
+                if input != self.media.input {
+                    return Task::none();
+                }
+                self.scrub_decoding = false;
+                match result {
+                    Ok((handle, _, hash, _)) => {
+                        self.last_scrub_preview_hash = hash;
+                        self.current_scrub_preview = Some(handle);
+                    }
+                    Err(PreviewError::SameHash) => (),
+                    Err(e) => log!("failed to decode the scrub preview: {e}"),
+                }
+                if std::mem::take(&mut self.scrub_pending) {
+                    return self.decode_scrub_preview();
+                }
+            }
+            Message::PreviewOffsetChange(offset) => {
+                self.preview_offset = offset;
+                return self.create_preview_images();
+            }
+            Message::ToggleProxy => {
+                self.use_proxy = !self.use_proxy;
+                return Task::batch([self.create_proxy(), self.create_preview_images()]);
+            }
+            Message::LoadedProxy(result) => match result {
+                Ok(_) => {
+                    self.proxy_ready = true;
+                    return self.create_preview_images();
+                }
+                Err(e) => {
+                    self.proxy = None;
+                    log!("failed to create the preview proxy: {e}")
                 }
-                Err(e) => self.error = e,
             },
-        }
-
-        Task::none()
-    }
-
-    fn view(&self) -> Element<'_, Message> {
-        let input_field = text_input("input file", &self.media.input)
-            .on_input(Message::InputChange)
-            .on_submit(Message::Submitted);
-        let input_picker =
-            button("pick file")
-                .on_press(Message::PickInput)
-                .style(if self.input_exists {
-                    button::primary
+            Message::LoadedKeyframes(result) => match result {
+                Ok(keyframes) => self.keyframes = keyframes,
+                Err(e) => log!("failed to find the keyframes: {e}"),
+            },
+            Message::LoadedWaveform(result) => match result {
+                Ok(handle) => self.waveform = Some(handle),
+                Err(e) => {
+                    self.waveform = None;
+                    log!("failed to render the waveform: {e}")
+                }
+            },
+            Message::LoadedLoudness(result) => match result {
+                Ok(loudness) => self.loudness = Some(loudness),
+                Err(e) => {
+                    self.loudness = None;
+                    log!("failed to measure loudness: {e}")
+                }
+            },
+            Message::LoadedReadableLength(input, readable) => {
+                if input == self.media.input {
+                    self.input_info.readable_length = readable;
+                }
+            }
+            Message::PlayAudio => {
+                if let Some(handle) = self.audio_playback_task_handle.take() {
+                    // dropping the playback stops ffplay
+                    handle.abort();
+                } else {
+                    let (start, dur) = self.clamped_span();
+                    let playback = AudioPlayback {
+                        input: self.media.input.clone(),
+                        start,
+                        dur,
+                    };
+                    let (task, handle) =
+                        Task::perform(playback.play(), Message::AudioPlayed).abortable();
+                    self.audio_playback_task_handle = Some(handle);
+                    return task;
+                }
+            }
+            Message::AudioPlayed(result) => {
+                self.audio_playback_task_handle = None;
+                if let Err(e) = result {
+                    self.error = e;
+                }
+            }
+            Message::LoadedStartPreview(Err(e), _) | Message::LoadedEndPreview(Err(e), _) => {
+                self.start_preview_scanned = None;
+                self.end_preview_scanned = None;
+                match e {
+                    PreviewError::SameHash => (),
+                    PreviewError::DecodeTimeout(_) => self.error = e.to_string(),
+                    _ => log!("{e}"),
+                }
+            }
+
+            Message::SheetRowsChange(str) => self.sheet.rows = str.parse().unwrap_or_default(),
+            Message::SheetColumnsChange(str) => {
+                self.sheet.columns = str.parse().unwrap_or_default()
+            }
+            Message::SheetWidthChange(str) => {
+                self.sheet.thumb_width = str.parse().unwrap_or_default()
+            }
+            Message::CreateSheet => {
+                self.error.clear();
+                self.creating_sheet = true;
+                let sheet = ContactSheet {
+                    input: self.media.input.clone(),
+                    length: self.input_info.length,
+                    ..self.sheet.clone()
+                };
+                let output = sheet_path(Path::new(&self.media.input));
+                return Task::perform(sheet.create(output), Message::SheetFinished);
+            }
+            Message::SheetFinished(result) => {
+                self.creating_sheet = false;
+                match result {
+                    Ok(path) => {
+                        self.status = format!("Created {}", path.display());
+                        self.last_output = Some(path);
+                    }
+                    Err(e) => self.error = e,
+                }
+            }
+
+            Message::StillsEveryChange(str) => self.stills.every = str.parse().ok(),
+            Message::StillsMaxChange(str) => {
+                self.stills.max_count = str.parse().unwrap_or_default()
+            }
+            Message::StillsFormatChange(format) => self.stills.format = format,
+            Message::ExportStills => {
+                self.error.clear();
+                self.exporting_stills = true;
+                let (start, end) = self.clamped_range();
+                let stills = StillExport {
+                    input: self.media.input.clone(),
+                    start,
+                    dur: Some(end - start),
+                    ..self.stills.clone()
+                };
+                let dir = stills_dir(Path::new(&self.media.input));
+                return Task::perform(stills.export(dir), Message::StillsFinished);
+            }
+            Message::StillsFinished(result) => {
+                self.exporting_stills = false;
+                match result {
+                    Ok(dir) => {
+                        self.status = format!("Exported the stills to {}", dir.display());
+                        self.last_output = Some(dir);
+                    }
+                    Err(e) => self.error = e,
+                }
+            }
+
+            Message::SampleLengthChange(str) => {
+                self.sample_length = str.parse().unwrap_or_default()
+            }
+            Message::CreateSample => {
+                self.error.clear();
+                self.creating_sample = true;
+                // the old sample is removed first, since the new one reuses the path
+                self.sample = None;
+
+                let output = sample_path(Path::new(&self.media.output));
+                let (start, dur) = self.clamped_span();
+                let sample = Media {
+                    start,
+                    dur: micros(self.sample_length).min(dur),
+                    output: output.to_string_lossy().to_string(),
+                    output_policy: OutputPolicy::Overwrite,
+                    split: Split::Off,
+                    extra_outputs: Vec::new(),
+                    padding: None,
+                    ..self.media.clone()
+                };
+                return Task::perform(
+                    async move {
+                        sample
+                            .run(|_| ())
+                            .await
+                            .map(|_| output)
+                            .map_err(|e| e.to_string())
+                    },
+                    Message::SampleFinished,
+                );
+            }
+            Message::SampleFinished(result) => {
+                self.creating_sample = false;
+                match result {
+                    Ok(path) => {
+                        self.sample = Some(TempFile(path.clone()));
+                        return Task::perform(open_path(path), Message::Opened);
+                    }
+                    Err(e) => self.error = format!("failed to create the sample: {e}"),
+                }
+            }
+
+            Message::MetadataTitleChange(str) => self.metadata_title = str,
+            Message::MetadataRotationChange(rotation) => self.metadata_rotation = Some(rotation),
+            Message::EditMetadata => {
+                return Task::perform(
+                    confirm_rewrite(PathBuf::from(&self.media.input)),
+                    Message::MetadataConfirmed,
+                );
+            }
+            Message::MetadataConfirmed(false) => (),
+            Message::MetadataConfirmed(true) => {
+                self.error.clear();
+                self.editing_metadata = true;
+                let edit = MetadataEdit {
+                    input: self.media.input.clone(),
+                    title: Some(self.metadata_title.clone()).filter(|title| !title.is_empty()),
+                    rotation: self.metadata_rotation,
+                };
+                return Task::perform(edit.apply(), Message::MetadataEdited);
+            }
+            Message::MetadataEdited(result) => {
+                self.editing_metadata = false;
+                match result {
+                    Ok(()) => {
+                        self.status = format!("Updated the metadata of {}", self.media.input);
+                        self.metadata_title.clear();
+                        self.metadata_rotation = None;
+                        // probed again, since the rotation changes the previews
+                        self.input_changed = true;
+                        return self.check_inputs();
+                    }
+                    Err(e) => self.error = e,
+                }
+            }
+
+            Message::SaveAttachment(i) => {
+                if let Some(attachment) = self.input_info.attachments.get(i) {
+                    return Task::perform(pick_save_path(attachment.default_name()), move |path| {
+                        Message::AttachmentPathPicked(i, path)
+                    });
+                }
+            }
+            Message::AttachmentPathPicked(i, Some(path)) => {
+                if let Some(attachment) = self.input_info.attachments.get(i) {
+                    self.error.clear();
+                    return Task::perform(
+                        attachment.clone().extract(self.media.input.clone(), path),
+                        Message::AttachmentSaved,
+                    );
+                }
+            }
+            Message::AttachmentPathPicked(_, None) => (),
+            Message::AttachmentSaved(result) => match result {
+                Ok(path) => self.status = format!("Saved {}", path.display()),
+                Err(e) => self.error = e,
+            },
+
+            Message::SaveJob => {
+                return Task::perform(
+                    pick_save_path("job.json".to_string()),
+                    Message::JobSavePathPicked,
+                );
+            }
+            Message::JobSavePathPicked(Some(path)) => {
+                // saved with the range it will be trimmed to, not what is still being typed
+                let (start, end) = self.clamped_range();
+                let job = Media {
+                    start,
+                    dur: end - start,
+                    ..self.media.clone()
+                };
+                match job.save_job(&path) {
+                    Ok(()) => self.status = format!("Saved {}", path.display()),
+                    Err(e) => self.error = format!("failed to save {}: {e}", path.display()),
+                }
+            }
+            Message::JobSavePathPicked(None) => (),
+            Message::LoadJob => return Task::perform(pick_file(), Message::JobPicked),
+            Message::JobPicked(Some(path)) => {
+                self.error.clear();
+                match Media::load_job(&path) {
+                    Ok(job) => return self.apply_job(job),
+                    Err(e) => self.error = e,
+                }
+            }
+            Message::JobPicked(None) => (),
+            Message::QueueSettingsForFiles => {
+                return Task::perform(pick_files(), Message::QueueSettingsFilesPicked);
+            }
+            Message::QueueSettingsFilesPicked(paths) => {
+                self.error.clear();
+                let extension = self
+                    .output_container()
+                    .map(|container| container.extension());
+                return Task::batch(paths.into_iter().map(|path| {
+                    Task::perform(
+                        job_for(self.media.clone(), path, extension),
+                        Message::SettingsJobReady,
+                    )
+                }));
+            }
+            Message::SettingsJobReady(Ok(media)) => self.queue_job(media),
+            Message::SettingsJobReady(Err(e)) => self.error = e,
+
+            Message::OpenPalette => {
+                self.palette = Some(String::new());
+                return operation::focus(PALETTE_ID);
+            }
+            Message::PaletteChange(query) => self.palette = Some(query),
+            Message::PaletteRun(index) => {
+                if let Some(query) = self.palette.take()
+                    && let Some((_, message)) = self.palette_matches(&query).into_iter().nth(index)
+                {
+                    return Task::done(message);
+                }
+            }
+
+            Message::Event(event, status) => {
+                // the new order is only saved once the dragged job is dropped
+                if let Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) = event
+                    && self.dragged_job.take().is_some()
+                {
+                    self.save_queue();
+                }
+                if let Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) = event {
+                    self.modifiers = modifiers;
+                }
+                if let Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) = event {
+                    // the hotkeys edit the settings too, which are locked while processing
+                    if self.processing {
+                        return Task::none();
+                    }
+                    // the palette takes the keyboard while it's open
+                    if self.palette.is_some() {
+                        if key == Key::Named(key::Named::Escape) {
+                            self.palette = None;
+                        }
+                        return Task::none();
+                    }
+
+                    // a focused text field takes plain keys, so only the combos act while typing
+                    let typing = status == event::Status::Captured;
+
+                    match key.as_ref() {
+                        Key::Character("p") if modifiers.command() => {
+                            return Task::done(Message::OpenPalette);
+                        }
+
+                        // input field cycling
+                        Key::Named(key::Named::Tab) => {
+                            if modifiers.shift() {
+                                return operation::focus_previous();
+                            } else {
+                                return operation::focus_next();
+                            }
+                        }
+
+                        Key::Named(key::Named::ArrowRight) | Key::Character("l") if !typing => {
+                            return if modifiers.shift() {
+                                Task::done(Message::EagerEndChange(self.end + micros(5.0)))
+                            } else {
+                                Task::done(Message::EagerStartChange(
+                                    self.media.start + micros(5.0),
+                                ))
+                            };
+                        }
+                        Key::Named(key::Named::ArrowLeft) | Key::Character("h") if !typing => {
+                            return if modifiers.shift() {
+                                Task::done(Message::EagerEndChange(self.end - micros(5.0)))
+                            } else {
+                                Task::done(Message::EagerStartChange(
+                                    self.media.start - micros(5.0),
+                                ))
+                            };
+                        }
+
+                        Key::Named(key::Named::ArrowUp) | Key::Character("k") if !typing => {
+                            return if modifiers.shift() {
+                                Task::done(Message::EagerEndChange(self.end + micros(10.0)))
+                            } else {
+                                Task::done(Message::EagerStartChange(
+                                    self.media.start + micros(10.0),
+                                ))
+                            };
+                        }
+                        Key::Named(key::Named::ArrowDown) | Key::Character("j") if !typing => {
+                            return if modifiers.shift() {
+                                Task::done(Message::EagerEndChange(self.end - micros(10.0)))
+                            } else {
+                                Task::done(Message::EagerStartChange(
+                                    self.media.start - micros(10.0),
+                                ))
+                            };
+                        }
+
+                        // early-exit hotkey
+                        Key::Named(key::Named::Escape)
+                            if !typing && self.settings.keymap.escape_quits =>
+                        {
+                            return window::latest().and_then(window::close);
+                        }
+
+                        Key::Character(c) if !typing => {
+                            if let Some(hotkey) = self.settings.keymap.hotkey(c) {
+                                return match hotkey {
+                                    Hotkey::ToggleVideo => Task::done(Message::ToggleVideo),
+                                    Hotkey::ToggleAudio => Task::done(Message::ToggleAudio),
+                                    Hotkey::ToggleSubs => Task::done(Message::ToggleSubs),
+                                    Hotkey::ToggleExtraStreams => {
+                                        Task::done(Message::ToggleExtraStreams)
+                                    }
+                                    Hotkey::ToggleConsole => Task::done(Message::ToggleConsole),
+                                    Hotkey::SwapBoundaries => Task::done(Message::SwapBoundaries),
+                                    Hotkey::Quit => window::latest().and_then(window::close),
+                                };
+                            }
+                        }
+
+                        Key::Named(key::Named::Enter) => {
+                            if modifiers.shift() {
+                                return Task::done(Message::Instantiate);
+                            } else {
+                                return focus_next();
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+            }
+
+            Message::Logged(line) => {
+                self.console.push_back(line);
+                if self.console.len() > log::MAX_LINES {
+                    self.console.pop_front();
+                }
+            }
+            Message::ToggleConsole => self.show_console = !self.show_console,
+
+            Message::AddToQueue => {
+                self.error.clear();
+                // queued with the range it will be trimmed to, not what is still being typed
+                let (start, dur) = self.clamped_span();
+                let media = Media {
+                    start,
+                    dur,
+                    ..self.media.clone()
+                };
+                self.queue_job(media);
+            }
+            Message::RemoveQueuedJob(index) => {
+                if index < self.queue.jobs.len()
+                    && self.queue.jobs[index].state != JobState::Running
+                {
+                    self.queue.jobs.remove(index);
+                    self.dragged_job = None;
+                    self.save_queue();
+                }
+            }
+            Message::DragQueuedJob(index) => {
+                if self
+                    .queue
+                    .jobs
+                    .get(index)
+                    .is_some_and(|job| job.state != JobState::Running)
+                {
+                    self.dragged_job = Some(index);
+                }
+            }
+            Message::DragOverQueuedJob(index) => {
+                if let Some(dragged) = self.dragged_job
+                    && self.queue.move_job(dragged, index)
+                {
+                    self.dragged_job = Some(index);
+                }
+            }
+            Message::RunQueue => {
+                self.confirming_queue =
+                    self.queue_task_handle.is_none() && self.queue.runnable().next().is_some();
+            }
+            Message::ConfirmRunQueue => {
+                self.confirming_queue = false;
+                self.queue_done = 0.0;
+                self.queue_speed_samples.clear();
+                self.queue_eta = None;
+                return self.run_next_job();
+            }
+            Message::CancelRunQueue => self.confirming_queue = false,
+            Message::StopQueue => {
+                // ffmpeg is killed along with the task
+                if let Some(handle) = self.queue_task_handle.take() {
+                    handle.abort();
+                }
+                if let Some(index) = self.queue.running() {
+                    self.queue.jobs[index].state = JobState::Interrupted;
+                    self.save_queue();
+                }
+                self.queue_progress = 0.0;
+                self.queue_eta = None;
+            }
+            Message::QueueProgress(progress) => {
+                if let Some(index) = self.queue.running() {
+                    self.queue_progress =
+                        progress.fraction(self.queue.jobs[index].media.output_dur());
+                }
+                if let Some(speed) = progress.speed.filter(|speed| *speed > 0.0) {
+                    self.queue_speed_samples.push_back(speed);
+                    if self.queue_speed_samples.len() > SPEED_SAMPLES {
+                        self.queue_speed_samples.pop_front();
+                    }
+                }
+
+                if !self.queue_speed_samples.is_empty() {
+                    let speed = self.queue_speed_samples.iter().sum::<f64>()
+                        / self.queue_speed_samples.len() as f64;
+                    let (written, total) = self.queue_secs();
+                    self.queue_eta = Some((total - written).max(0.0) / speed);
+                }
+            }
+            Message::QueuedJobFinished(result) => {
+                self.queue_task_handle = None;
+                self.queue_progress = 0.0;
+                let Some(index) = self.queue.running() else {
+                    self.queue_eta = None;
+                    return Task::none();
+                };
+                // a failed job is done with too, as far as the rest of the run goes
+                self.queue_done += self.queue.jobs[index].media.output_dur();
+                match result {
+                    Ok(status) => {
+                        log!("{status}: {}", self.queue.jobs[index].media.output);
+                        // finished jobs leave the queue, so they aren't restored
+                        self.queue.jobs.remove(index);
+                        // the dragged job's index may have shifted
+                        self.dragged_job = None;
+                    }
+                    Err(e) => self.queue.jobs[index].state = JobState::Failed(e),
+                }
+                self.save_queue();
+                let task = self.run_next_job();
+                if self.queue_task_handle.is_none() {
+                    self.queue_eta = None;
+                }
+                return task;
+            }
+            Message::RestoreQueue => {
+                if let Some(restored) = self.restorable_queue.take() {
+                    // ahead of anything queued since launching
+                    self.queue.jobs.splice(0..0, restored.jobs);
+                    self.save_queue();
+                }
+            }
+            Message::DiscardQueue => {
+                self.restorable_queue = None;
+                self.save_queue();
+            }
+
+            Message::Instantiate => {
+                if self.processing {
+                    return Task::none();
+                }
+                self.error.clear();
+                self.suggest_timestamp_fix = false;
+                self.speed_samples.clear();
+                self.eta = None;
+                self.progress = 0.0;
+                self.output_started = false;
+                self.status = "Loading...".to_string();
+                self.processing = true;
+
+                let (task, handle) = self.instantiate().abortable();
+                self.instantiate_task_handle = Some(handle);
+                return task;
+            }
+            Message::CancelInstantiate => {
+                // ffmpeg is killed along with the task, and reaped by smol's process reaper
+                if let Some(handle) = self.instantiate_task_handle.take() {
+                    handle.abort();
+                }
+                self.processing = false;
+                self.eta = None;
+                self.speed_samples.clear();
+                self.progress = 0.0;
+                self.status.clear();
+
+                // before ffmpeg starts, an existing output is still intact and stays
+                if !self.output_started {
+                    self.error = "Cancelled".to_string();
+                    return Task::none();
+                }
+                self.output_started = false;
+                let mut removed = true;
+                for path in self.media.outputs().iter().filter(|path| path.exists()) {
+                    if let Err(e) = std::fs::remove_file(path) {
+                        log!(
+                            "failed to remove the partial output {}: {e}",
+                            path.display()
+                        );
+                        removed = false;
+                    }
+                }
+                self.error = if removed {
+                    "Cancelled, the partial output was removed".to_string()
+                } else {
+                    "Cancelled, the output may be incomplete".to_string()
+                };
+            }
+            Message::Progress(progress) => {
+                self.output_started = true;
+                let total = self.media.output_dur();
+                self.progress = progress.fraction(total);
+                if let Some(speed) = progress.speed.filter(|speed| *speed > 0.0) {
+                    self.speed_samples.push_back(speed);
+                    if self.speed_samples.len() > SPEED_SAMPLES {
+                        self.speed_samples.pop_front();
+                    }
+                }
+
+                if !self.speed_samples.is_empty() {
+                    let speed =
+                        self.speed_samples.iter().sum::<f64>() / self.speed_samples.len() as f64;
+                    self.eta = Some((total - progress.out_time).max(0.0) / speed);
+                }
+            }
+            Message::InstantiateFinished(result) => {
+                self.processing = false;
+                self.instantiate_task_handle = None;
+                match result {
+                    Ok(status) => {
+                        self.eta = None;
+                        self.status = status;
+                        self.last_output = Some(self.media.first_output());
+                        self.settings.last_output = self.last_output.clone();
+                        if let Err(e) = self.settings.save() {
+                            log!("failed to save the settings: {e}");
+                        }
+                        // stays open to offer the retry for an output that will likely glitch
+                        if !self.keep_open && !self.suggest_timestamp_fix {
+                            return window::latest().and_then(window::close);
+                        }
+                    }
+                    Err(e) => {
+                        self.eta = None;
+                        self.error = e
+                    }
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let input_field = text_input("input file", &self.media.input)
+            .on_input(Message::InputChange)
+            .on_submit(Message::Submitted);
+        let input_picker =
+            button("pick file")
+                .on_press(Message::PickInput)
+                .style(if self.input_exists {
+                    button::primary
                 } else {
                     button::warning
                 });
+        let input_args_field = text_input("-probesize 100M", &self.input_args_field)
+            .on_input(Message::InputArgsChange)
+            .on_submit(Message::InputArgsSubmitted)
+            .width(300);
+        let input_args_error = self
+            .input_args_error
+            .as_ref()
+            .map(|e| text(format!("invalid input options: {e}")).style(text::danger));
 
+        let restorable_queue = self.restorable_queue.as_ref().map(|queue| {
+            row![
+                text(format!(
+                    "{} jobs were left in the queue last time",
+                    queue.jobs.len()
+                )),
+                button("restore").on_press(Message::RestoreQueue),
+                button("discard")
+                    .on_press(Message::DiscardQueue)
+                    .style(button::secondary)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let queue = (!self.queue.jobs.is_empty()).then(|| {
+            let running = self.queue_task_handle.is_some();
+            let controls = row![
+                text(format!("Queue ({} jobs):", self.queue.jobs.len())),
+                if running {
+                    button("stop")
+                        .on_press(Message::StopQueue)
+                        .style(button::danger)
+                } else {
+                    button("run").on_press_maybe(
+                        self.queue
+                            .runnable()
+                            .next()
+                            .is_some()
+                            .then_some(Message::RunQueue),
+                    )
+                }
+            ]
+            .push(running.then(|| {
+                let (written, total) = self.queue_secs();
+                let fraction = if total > 0.0 { written / total } else { 0.0 };
+                progress_bar(0.0..=1.0, fraction as f32)
+                    .length(200)
+                    .girth(10)
+            }))
+            .push(running.then(|| text(self.queue_eta.map(format_eta).unwrap_or_default())))
+            .spacing(10)
+            .align_y(Vertical::Center);
+
+            column![controls]
+                .extend(self.queue.jobs.iter().enumerate().map(|(i, job)| {
+                    let state = match &job.state {
+                        JobState::Pending => text("pending").style(text::secondary),
+                        JobState::Running => {
+                            text(format!("running, {:.0}%", self.queue_progress * 100.0))
+                                .style(text::primary)
+                        }
+                        JobState::Interrupted => text("interrupted").style(text::warning),
+                        JobState::Failed(e) => text(format!("failed: {e}")).style(text::danger),
+                    };
+                    let running = job.state == JobState::Running;
+                    // running jobs can't be dragged, or have others dropped in their place
+                    let handle = (!running).then(|| {
+                        mouse_area(text("=").style(if self.dragged_job == Some(i) {
+                            text::primary
+                        } else {
+                            text::secondary
+                        }))
+                        .on_press(Message::DragQueuedJob(i))
+                        .interaction(mouse::Interaction::Grab)
+                    });
+                    let row = row![]
+                        .push(handle)
+                        .push(text(format!("{} -> {}", job.media.input, job.media.output)))
+                        .push(state)
+                        .push((!running).then(|| {
+                            button("remove")
+                                .on_press(Message::RemoveQueuedJob(i))
+                                .style(button::secondary)
+                        }))
+                        .spacing(10)
+                        .align_y(Vertical::Center);
+
+                    if running {
+                        row.into()
+                    } else {
+                        mouse_area(row)
+                            .on_enter(Message::DragOverQueuedJob(i))
+                            .into()
+                    }
+                }))
+                .spacing(5)
+        });
+
+        let previous_output = self.previous_output.as_ref().map(|path| {
+            row![
+                text(format!("Last time you made {}", path.display())),
+                button("load as input").on_press(Message::LoadPreviousOutput),
+                button("reveal")
+                    .on_press(Message::RevealOutput)
+                    .style(button::secondary),
+                button("dismiss")
+                    .on_press(Message::DismissPreviousOutput)
+                    .style(button::secondary),
+                checkbox(self.settings.offer_last_output)
+                    .on_toggle(|_| Message::ToggleOfferLastOutput)
+                    .label("offer on launch")
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        // the sliders only count in seconds, which the messages turn back into microseconds
+        let start_range = 0..=(self.end - MIN_GAP).max(0);
         let start_slider = slider(
-            0_f64..=self.end - 1.0,
-            self.media.start,
-            Message::EagerStartChange,
+            0.0..=secs(*start_range.end()),
+            secs(self.media.start),
+            |start| Message::EagerStartChange(micros(start)),
         )
+        .step(SLIDER_STEP)
         .default(0);
-        let start_field = text_input("start", &self.media.start.to_string())
-            .on_input(|str| Message::StartChange(str.parse().unwrap_or_default()))
+        // the ticks go underneath, so they don't cover the handle
+        let start_slider = stack![]
+            .push(self.keyframe_ticks(start_range))
+            .push(mouse_area(start_slider).on_scroll(Message::StartScrolled));
+        // frame numbers and percentages are only used in the fields, everything else stays in seconds
+        let (fps, length) = self.field_units();
+        let to_field = |time: i64| match (fps, length) {
+            (Some(fps), _) => (secs(time) * fps).round().to_string(),
+            (None, Some(length)) => ((secs(time) / length * 10_000.0).round() / 100.0).to_string(),
+            (None, None) => secs(time).to_string(),
+        };
+        let unit = match (fps, length) {
+            (Some(_), _) => "frame",
+            (None, Some(_)) => "%",
+            (None, None) => "seconds",
+        };
+
+        let start_field = text_input("start", &to_field(self.media.start))
+            .on_input(|str| Message::StartChange(self.time_from_field(&str)))
             .width(200)
             .on_submit(Message::Submitted);
+        let start_reset = button("reset")
+            .on_press(Message::EagerStartChange(0))
+            .style(button::secondary);
 
+        let end_range = (self.media.start + MIN_GAP).min(self.input_length)..=self.input_length;
         let end_slider = slider(
-            self.media.start + 1.0..=self.input_length,
-            self.end,
-            Message::EagerEndChange,
+            secs(*end_range.start())..=secs(*end_range.end()),
+            secs(self.end),
+            |end| Message::EagerEndChange(micros(end)),
         )
-        .default(self.input_length);
-        let end_field = text_input("end", &self.end.to_string())
-            .on_input(|str| Message::EndChange(str.parse().unwrap_or_default()))
+        .step(SLIDER_STEP)
+        .default(secs(self.input_length));
+        let end_slider = stack![]
+            .push(self.keyframe_ticks(end_range))
+            .push(mouse_area(end_slider).on_scroll(Message::EndScrolled));
+        let end_field = text_input("end", &to_field(self.end))
+            .on_input(|str| Message::EndChange(self.time_from_field(&str)))
             .width(200)
             .on_submit(Message::Submitted);
+        let end_reset = button("reset")
+            .on_press(Message::EagerEndChange(self.input_length))
+            .style(button::secondary);
+        // negative ends are resolved once submitted, show what they will become
+        let resolved_end = (self.end < 0).then(|| {
+            text(format!(
+                "= {}",
+                to_field((self.input_length + self.end).max(0))
+            ))
+        });
+        let converted = fps.is_some() || length.is_some();
+        let start_timecode = converted.then(|| text(format_timecode(self.media.start)));
+        let end_timecode = converted.then(|| text(format_timecode(self.end.max(0))));
+        let scroll_step_field = row![
+            text("Scroll step (seconds):"),
+            tooltip(
+                text_input("", &self.scroll_step_field)
+                    .on_input(Message::ScrollStepChange)
+                    .width(60),
+                container(text(
+                    "how far a notch of the mouse wheel moves a time slider\n\
+                     hold shift for ten times as far, or ctrl for a tenth",
+                ))
+                .padding(10)
+                .style(container::rounded_box),
+                tooltip::Position::Top,
+            )
+        ]
+        .push(
+            parse_scroll_step(&self.scroll_step_field)
+                .is_none()
+                .then(|| text("invalid step, expected seconds").style(text::danger)),
+        )
+        .spacing(10)
+        .align_y(Vertical::Center);
+        let frame_mode_checkbox = checkbox(self.frame_mode)
+            .on_toggle_maybe(self.input_info.fps.map(|_| |_| Message::ToggleFrameMode))
+            .label("frame numbers");
+        let percent_mode_checkbox = checkbox(self.percent_mode)
+            .on_toggle_maybe(
+                self.has_duration()
+                    .then_some(|_| Message::TogglePercentMode),
+            )
+            .label("percentages");
 
         let output_field = text_input("output file", &self.media.output)
             .on_input(|str| Message::OutputChange(str, false))
             .on_submit(Message::Submitted);
+        let output_generated_tag = self.output_is_generated.then(|| {
+            tooltip(
+                text("(auto)").style(text::secondary),
+                container(text(
+                    "named after the input, so it changes along with it\n\
+                     editing the output keeps it as it is",
+                ))
+                .padding(10)
+                .style(container::rounded_box),
+                tooltip::Position::Top,
+            )
+        });
+        let container_list = pick_list(
+            Container::ALL,
+            Container::of(&self.media.output),
+            Message::FormatChange,
+        )
+        .placeholder("container");
+        let output_policy_list = pick_list(
+            OutputPolicy::ALL,
+            Some(self.media.output_policy),
+            Message::OutputPolicyChange,
+        );
+        let mp4_layout = is_mp4(&self.media.output).then(|| {
+            row![
+                text("MP4 layout:"),
+                pick_list(
+                    Mp4Layout::ALL,
+                    Some(self.media.mp4_layout),
+                    Message::Mp4LayoutChange
+                )
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+        let low_priority_checkbox = checkbox(self.media.low_priority)
+            .on_toggle(|_| Message::ToggleLowPriority)
+            .label("low priority");
         let output_picker = button("pick folder").on_press(Message::PickOutput).style(
             if self.output_folder_exists {
                 button::primary
@@ -340,6 +1990,20 @@ impl State {
         let audio_checkbox = checkbox(self.media.use_audio)
             .on_toggle(|_| Message::ToggleAudio)
             .label("audio");
+        let loudness_text = match self.loudness {
+            Some(loudness) if self.media.use_audio => text(format!(
+                "{:.1} LUFS, peak {:.1} dBTP",
+                loudness.integrated, loudness.true_peak
+            )),
+            _ => text(""),
+        };
+        let playing_audio = self.audio_playback_task_handle.is_some();
+        let play_audio_button = button(if playing_audio { "stop" } else { "play audio" })
+            .on_press_maybe(
+                (playing_audio || self.media.use_audio && self.input_exists)
+                    .then_some(Message::PlayAudio),
+            )
+            .style(button::secondary);
         let subs_checkbox = checkbox(self.media.use_subs)
             .on_toggle(|_| Message::ToggleSubs)
             .label("subtitles");
@@ -347,209 +2011,2604 @@ impl State {
             .on_toggle(|_| Message::ToggleExtraStreams)
             .label("extra streams");
 
-        let preview_row = if self.media.use_video
-            && let Some(h_start) = self.start_preview.clone()
-            && let Some(h_end) = self.end_preview.clone()
-        {
+        // the auto-detected toggles usually match "everything"
+        let current_preset = StreamPreset::matching(&self.media, &self.input_info);
+        let stream_presets = row(StreamPreset::ALL.into_iter().map(|preset| {
+            radio(
+                preset.to_string(),
+                preset,
+                current_preset,
+                Message::StreamPresetChange,
+            )
+            .into()
+        }))
+        .spacing(40);
+
+        let attachments =
+            (!self.input_info.attachments.is_empty()).then(|| {
+                row![text("Attachments:")]
+                    .extend(self.input_info.attachments.iter().enumerate().map(
+                        |(i, attachment)| {
+                            button(text(format!("save {}", attachment.default_name())).size(12))
+                                .on_press(Message::SaveAttachment(i))
+                                .style(button::secondary)
+                                .into()
+                        },
+                    ))
+                    .spacing(10)
+                    .align_y(Vertical::Center)
+                    .wrap()
+            });
+
+        let chapters = (!self.input_info.chapters.is_empty()).then(|| {
+            let current = self
+                .input_info
+                .chapters
+                .iter()
+                // the last chapter can end past the duration, which the end gets clamped to
+                .find(|chapter| {
+                    chapter.start == self.media.start
+                        && chapter.end.min(self.input_length) == self.end
+                })
+                .cloned();
             row![
-                Image::<image::Handle>::new(h_start)
-                    .width(Length::Fill)
-                    .height(Length::Fill),
-                Image::<image::Handle>::new(h_end)
+                text("Trim to chapter:"),
+                pick_list(
+                    self.input_info.chapters.as_slice(),
+                    current,
+                    Message::ChapterPicked
+                )
+                .placeholder("pick a chapter"),
+                checkbox(self.media.drop_chapters)
+                    .on_toggle(|_| Message::ToggleDropChapters)
+                    .label("drop chapters")
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let subtitle_row = self.media.use_subs.then(|| {
+            row![
+                text("Subtitle codec:"),
+                pick_list(
+                    SubtitleCodec::ALL,
+                    Some(self.media.sub_codec),
+                    Message::SubtitleCodecChange
+                ),
+                (self.media.sub_codec == SubtitleCodec::Auto).then(|| text(format!(
+                    "({})",
+                    self.media.sub_codec.resolve(&self.media.output)
+                ))),
+                self.media
+                    .subtitle_warning(&self.input_info)
+                    .map(|warning| text(warning).style(text::danger))
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let defaults = &self.settings.default_streams;
+        let default_streams = row![
+            text("Enable on load:"),
+            checkbox(defaults.video)
+                .on_toggle(|_| Message::ToggleDefaultStream(StreamKind::Video))
+                .label("video"),
+            checkbox(defaults.audio)
+                .on_toggle(|_| Message::ToggleDefaultStream(StreamKind::Audio))
+                .label("audio"),
+            checkbox(defaults.subs)
+                .on_toggle(|_| Message::ToggleDefaultStream(StreamKind::Subs))
+                .label("subtitles"),
+            checkbox(defaults.extra_streams)
+                .on_toggle(|_| Message::ToggleDefaultStream(StreamKind::ExtraStreams))
+                .label("extra streams"),
+        ]
+        .spacing(20)
+        .align_y(Vertical::Center);
+
+        let audio_offset_row = self.media.use_audio.then(|| {
+            row![
+                text("Audio offset:"),
+                slider(
+                    -AUDIO_OFFSET_RANGE..=AUDIO_OFFSET_RANGE,
+                    self.media.audio_offset,
+                    Message::AudioOffsetChange
+                )
+                .step(0.01)
+                .default(0.0)
+                .width(300),
+                text(format!("{:+.0} ms", self.media.audio_offset * 1000.0)),
+                button("reset")
+                    .on_press(Message::AudioOffsetChange(0.0))
+                    .style(button::secondary)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let preview_offset_row = self.media.use_video.then(|| {
+            row![
+                text("Preview offset:"),
+                slider(
+                    -PREVIEW_OFFSET_RANGE..=PREVIEW_OFFSET_RANGE,
+                    self.preview_offset,
+                    Message::PreviewOffsetChange
+                )
+                .step(0.01)
+                .default(0.0)
+                .width(300),
+                text(format!("{:+.2} s from each boundary", self.preview_offset)),
+                button("reset")
+                    .on_press(Message::PreviewOffsetChange(0.0))
+                    .style(button::secondary)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let scrub_row = (self.show_scrub && self.media.use_video).then(|| {
+            column![
+                row![
+                    slider(
+                        0.0..=secs(self.input_length),
+                        self.scrub_position,
+                        Message::ScrubChange
+                    )
+                    .step(SLIDER_STEP),
+                    text(format_timecode(micros(self.scrub_position))),
+                    button("use as start")
+                        .on_press(Message::EagerStartChange(micros(self.scrub_position)))
+                        .style(button::secondary),
+                    button("use as end")
+                        .on_press(Message::EagerEndChange(micros(self.scrub_position)))
+                        .style(button::secondary)
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center)
+            ]
+            .push(self.current_scrub_preview.clone().map(|handle| {
+                Image::<image::Handle>::new(handle)
                     .width(Length::Fill)
                     .height(Length::Fill)
-            ]
-        } else {
-            row![]
-        };
+            }))
+            .spacing(10)
+        });
 
-        let status_display = if !self.error.is_empty() {
+        let silence_row = self.media.use_audio.then(|| {
+            row![
+                text("Trim silence quieter than"),
+                text_input("", &self.silence.threshold.to_string())
+                    .on_input(Message::SilenceThresholdChange)
+                    .width(60),
+                text("dB, lasting at least"),
+                text_input("", &self.silence_duration_field)
+                    .on_input(Message::SilenceDurationChange)
+                    .width(60),
+                text("seconds, from the ends:"),
+                button(if self.detecting_silence {
+                    "detecting..."
+                } else {
+                    "detect"
+                })
+                .on_press_maybe(
+                    (self.input_exists && !self.detecting_silence)
+                        .then_some(Message::DetectSilence)
+                )
+            ]
+            .push(
+                parse_silence_duration(&self.silence_duration_field)
+                    .is_none()
+                    .then(|| text("invalid duration, expected seconds").style(text::danger)),
+            )
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let video_codec_list = pick_list(
+            self.available_codecs.as_slice(),
+            Some(self.media.video_codec),
+            Message::VideoCodecChange,
+        );
+        let pix_fmt_list = pick_list(
+            PixelFormat::ALL,
+            Some(self.media.pix_fmt),
+            Message::PixelFormatChange,
+        );
+        let source_pix_fmt = text(format!(
+            "(source: {})",
+            self.input_info.pix_fmt.as_deref().unwrap_or("unknown")
+        ));
+        // copying keeps the source format, and so does re-encoding without picking one
+        let bit_depth_warning = (self.media.use_video
+            && self.input_info.is_high_bit_depth()
+            && (self.media.video_codec == VideoCodec::Copy
+                || self.media.pix_fmt == PixelFormat::Source))
+            .then(|| {
+                text("high bit depth video won't play everywhere, consider re-encoding to yuv420p")
+                    .style(text::danger)
+            });
+
+        let alpha_warning = if self.media.pix_fmt == PixelFormat::Yuva420p
+            && !self.media.video_codec.supports_alpha()
+        {
+            Some(
+                text(format!(
+                    "{} can't encode yuva420p, pick VP9",
+                    self.media.video_codec
+                ))
+                .style(text::danger),
+            )
+        } else if !(self.media.use_video && self.input_info.has_alpha) {
+            None
+        } else if !self.media.video_codec.supports_alpha() {
+            Some(
+                text(format!(
+                    "{} drops the transparency, VP9 keeps it",
+                    self.media.video_codec
+                ))
+                .style(text::warning),
+            )
+        } else if !self.media.pix_fmt.keeps_alpha() {
+            Some(
+                text(format!(
+                    "{} drops the transparency, yuva420p keeps it",
+                    self.media.pix_fmt
+                ))
+                .style(text::warning),
+            )
+        } else {
+            None
+        };
+
+        let compare_field = text_input("second input to compare with", &self.compare_input)
+            .on_input(Message::CompareInputChange);
+        let compare_picker = button("pick file")
+            .on_press(Message::PickCompareInput)
+            .style(button::secondary);
+        let compare_layout_list = pick_list(
+            CompareLayout::ALL,
+            Some(self.compare_layout),
+            Message::CompareLayoutChange,
+        );
+        let compare_warning = self.media.compare.is_some().then(|| {
+            text("comparing re-encodes the video and only keeps the first input's audio")
+                .style(text::warning)
+        });
+
+        let watermark_row = row![
+            text("Watermark:"),
+            button(if self.media.watermark.is_some() {
+                "pick another image"
+            } else {
+                "pick image"
+            })
+            .on_press(Message::PickWatermark)
+            .style(button::secondary)
+        ]
+        .push(self.media.watermark.as_ref().map(|watermark| {
+            row![
+                text(&watermark.image).style(text::secondary),
+                pick_list(
+                    Corner::ALL,
+                    Some(watermark.corner),
+                    Message::WatermarkCornerChange
+                ),
+                text(format!("opacity {:.0}%", watermark.opacity * 100.0)),
+                slider(
+                    0.0..=1.0,
+                    watermark.opacity,
+                    Message::WatermarkOpacityChange
+                )
+                .step(0.05)
+                .width(150),
+                button("remove")
+                    .on_press(Message::RemoveWatermark)
+                    .style(button::secondary)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        }))
+        .push(
+            (self.media.watermark.is_some() && self.media.video_codec == VideoCodec::Copy).then(
+                || text("the watermark re-encodes the video with libx264").style(text::warning),
+            ),
+        )
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let crop_field = text_input("w:h:x:y", &self.crop_field)
+            .on_input(Message::CropChange)
+            .width(160);
+        let detect_crop_button = button(if self.detecting_crop {
+            "detecting..."
+        } else {
+            "detect crop"
+        })
+        .on_press_maybe(self.allowed(Message::DetectCrop));
+        let suggested_crop = self.suggested_crop.map(|crop| {
+            row![
+                text(format!("suggested: {crop}")),
+                button("apply").on_press(Message::ApplyCrop)
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+        let crop_warning = if !self.crop_field.is_empty() && self.media.crop.is_none() {
+            Some(text("invalid crop, expected w:h:x:y").style(text::danger))
+        } else if self.media.crop.is_some() && self.media.video_codec == VideoCodec::Copy {
+            Some(text("cropping needs a video codec other than copy").style(text::danger))
+        } else {
+            None
+        };
+
+        let keyframe_field = text_input("encoder default", &self.keyframe_field)
+            .on_input(Message::KeyframeIntervalChange)
+            .width(160);
+        let force_keyframes_checkbox = checkbox(self.media.force_keyframes)
+            .on_toggle_maybe(
+                self.media
+                    .keyframe_interval
+                    .map(|_| |_| Message::ToggleForceKeyframes),
+            )
+            .label("force at exact times");
+        let keyframe_warning =
+            if !self.keyframe_field.is_empty() && self.media.keyframe_interval.is_none() {
+                Some(text("invalid keyframe interval, expected seconds").style(text::danger))
+            } else if let Err(e) = self.media.validate_keyframe_interval() {
+                Some(text(e).style(text::danger))
+            } else if self.media.keyframe_interval.is_some()
+                && self.media.video_codec == VideoCodec::Copy
+            {
+                Some(text("keyframes can only be placed when re-encoding").style(text::warning))
+            } else {
+                None
+            };
+
+        let pad_row = row![
+            text("Pad to"),
+            text_input("off", &self.pad_field)
+                .on_input(Message::PadLengthChange)
+                .width(80),
+            text("seconds,"),
+            pick_list(
+                PadPlacement::ALL,
+                Some(self.pad_placement),
+                Message::PadPlacementChange
+            ),
+            text("with"),
+            pick_list(PadFill::ALL, Some(self.pad_fill), Message::PadFillChange)
+        ]
+        .push(
+            if !self.pad_field.is_empty() && self.media.padding.is_none() {
+                Some(text("invalid length, expected seconds").style(text::danger))
+            } else {
+                self.media.padding.map(|padding| {
+                    if padding.length < secs(self.media.dur) {
+                        return text("the range is already longer than that").style(text::danger);
+                    }
+                    let (before, after) = padding.amounts(secs(self.media.dur));
+                    text(format!(
+                        "adds {before:.2}s before and {after:.2}s after, which re-encodes"
+                    ))
+                    .style(text::secondary)
+                })
+            },
+        )
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let encoder_fallbacks = self.media.video_codec.is_hardware().then(|| {
+            row![
+                text("If it fails, try:"),
+                text_input("libx264", &self.encoder_fallbacks_field)
+                    .on_input(Message::EncoderFallbacksChange)
+                    .width(300),
+                text("(encoder names, in order)").style(text::secondary)
+            ]
+            .push(
+                self.encoder_fallbacks_error
+                    .as_ref()
+                    .map(|e| text(e).style(text::danger)),
+            )
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let crf_row = self.media.video_codec.max_crf().map(|max_crf| {
+            row![
+                checkbox(self.media.crf.is_some())
+                    .on_toggle(|_| Message::ToggleCrf)
+                    .label("constant rate factor"),
+            ]
+            .push(self.media.crf.map(|crf| {
+                row![
+                    slider(0..=max_crf, crf, Message::CrfChange).width(200),
+                    text(crf.to_string()),
+                    text("(lower is better quality and bigger)").style(text::secondary)
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center)
+            }))
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let codec_warning = if self.media.video_codec.is_slow() {
+            Some(text("AV1 encodes slowly, expect this to take a while").style(text::warning))
+        } else if self.media.video_codec.is_webm() && !self.media.output.ends_with(".webm") {
+            Some(text("this codec needs a .webm output").style(text::danger))
+        } else {
+            None
+        };
+
+        let split_parts_field = text_input(
+            "parts",
+            &match self.media.split {
+                Split::Parts(parts) => parts.to_string(),
+                _ => String::new(),
+            },
+        )
+        .on_input(Message::SplitPartsChange)
+        .width(80);
+        let split_max_field = text_input(
+            "seconds",
+            &match self.media.split {
+                Split::MaxDuration(max) => max.to_string(),
+                _ => String::new(),
+            },
+        )
+        .on_input(Message::SplitMaxChange)
+        .width(80);
+
+        let copy_ts_checkbox = tooltip(
+            checkbox(self.media.copy_ts)
+                .on_toggle(|_| Message::ToggleCopyTimestamps)
+                .label("keep source timestamps"),
+            container(text(
+                "off: every output starts at zero, best for standalone clips\n\
+                 on: timestamps continue from the input, so parts concatenate cleanly",
+            ))
+            .padding(10)
+            .style(container::rounded_box),
+            tooltip::Position::Top,
+        );
+        let fix_timestamps_checkbox = tooltip(
+            checkbox(self.media.fix_timestamps)
+                .on_toggle(|_| Message::ToggleFixTimestamps)
+                .label("fix timestamps"),
+            container(text(
+                "regenerates missing timestamps and writes packets strictly in order,\n\
+                 for copies that glitch with \"non-monotonous DTS\" warnings",
+            ))
+            .padding(10)
+            .style(container::rounded_box),
+            tooltip::Position::Top,
+        );
+
+        let shortest_checkbox = tooltip(
+            checkbox(!self.media.keep_stream_tails)
+                .on_toggle(|_| Message::ToggleShortest)
+                .label("end with the shortest stream"),
+            container(text(
+                "on: the output ends with whichever stream ends first, so the streams end together,\n\
+                 which loops and concatenates cleanly but can drop a few frames or samples\n\
+                 off: every stream keeps its tail, so one can run past the other",
+            ))
+            .padding(10)
+            .style(container::rounded_box),
+            tooltip::Position::Top,
+        );
+
+        let extra_outputs = column(self.media.extra_outputs.iter().enumerate().map(
+            |(i, extra)| {
+                row![
+                    text("Also write:"),
+                    text_input("output", &extra.output)
+                        .on_input(move |str| Message::ExtraOutputChange(i, str))
+                        .width(400),
+                    pick_list(
+                        self.available_codecs.as_slice(),
+                        Some(extra.video_codec),
+                        move |codec| Message::ExtraOutputCodecChange(i, codec),
+                    ),
+                    button("remove")
+                        .on_press(Message::RemoveExtraOutput(i))
+                        .style(button::secondary)
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center)
+                .into()
+            },
+        ))
+        .spacing(10)
+        .push(
+            // comparing builds a filtergraph for a single output
+            button("add output")
+                .on_press_maybe(
+                    self.media
+                        .compare
+                        .is_none()
+                        .then_some(Message::AddExtraOutput),
+                )
+                .style(button::secondary),
+        );
+
+        let exact_preview_checkbox = checkbox(self.exact_preview)
+            .on_toggle(|_| Message::ToggleExactPreview)
+            .label("exact preview (slower)");
+        let proxy_checkbox = checkbox(self.use_proxy)
+            .on_toggle(|_| Message::ToggleProxy)
+            .label(if self.proxy.is_some() && !self.proxy_ready {
+                "low-res proxy (encoding...)"
+            } else {
+                "low-res proxy"
+            });
+        let preview_size_list = pick_list(
+            PreviewSize::ALL,
+            Some(self.preview_size),
+            Message::PreviewSizeChange,
+        );
+        let histogram_checkbox = checkbox(self.show_histogram)
+            .on_toggle(|_| Message::ToggleHistogram)
+            .label("histogram");
+        let decode_time_checkbox = checkbox(self.show_decode_time)
+            .on_toggle(|_| Message::ToggleDecodeTime)
+            .label("decode time");
+        let decode_time = self.show_decode_time.then(|| {
+            let format = |took: Option<Duration>| {
+                took.map_or("-".to_string(), |took| format!("{} ms", took.as_millis()))
+            };
+            text(format!(
+                "start: {}, end: {}",
+                format(self.start_decode_time),
+                format(self.end_decode_time)
+            ))
+        });
+        let frame_times_checkbox = checkbox(self.show_frame_times)
+            .on_toggle(|_| Message::ToggleFrameTimes)
+            .label("frame times");
+        let frame_times = self
+            .show_frame_times
+            .then(|| {
+                text(format!(
+                    "start: {}, end: {}",
+                    self.describe_frame_time(self.start_frame_time),
+                    self.describe_frame_time(self.end_frame_time)
+                ))
+                .style(text::secondary)
+            })
+            .filter(|_| self.media.use_video && self.start_preview.is_some());
+        let history_checkbox = checkbox(self.log_history)
+            .on_toggle(|_| Message::ToggleHistory)
+            .label("keep history");
+        let history_button = button("open history").on_press_maybe(
+            history_path()
+                .is_some_and(|path| path.exists())
+                .then_some(Message::OpenHistory),
+        );
+
+        let preview_scanning = (self.start_preview_scanned.is_some()
+            || self.end_preview_scanned.is_some())
+        .then(|| {
+            let format =
+                |scanned: Option<usize>| scanned.map_or("-".to_string(), |n| n.to_string());
+            text(format!(
+                "still seeking, packets scanned: start {}, end {}",
+                format(self.start_preview_scanned),
+                format(self.end_preview_scanned)
+            ))
+            .style(text::secondary)
+        });
+
+        let preview_row = if self.media.use_video
+            && let Some(h_start) = self.start_preview.clone()
+            && let Some(h_end) = self.end_preview.clone()
+        {
+            let histogram = |histogram: &Option<Histogram>| {
+                histogram
+                    .as_ref()
+                    .filter(|_| self.show_histogram)
+                    .map(|histogram| {
+                        container(histogram_view(histogram)).align_bottom(Length::Fill)
+                    })
+            };
+
+            row![
+                stack![
+                    Image::<image::Handle>::new(h_start)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                ]
+                .push(histogram(&self.start_histogram)),
+                stack![
+                    Image::<image::Handle>::new(h_end)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                ]
+                .push(histogram(&self.end_histogram))
+            ]
+        } else if self.input_info.is_audio_only() {
+            // where the range sits in the input, above the waveform of the range itself
+            let length = secs(self.input_length).max(f64::EPSILON);
+            let position =
+                |time: i64| ((secs(time) / length).clamp(0.0, 1.0) * 1000.0).round() as u16;
+            row![
+                column![
+                    row![
+                        space().width(Length::FillPortion(position(self.media.start).max(1))),
+                        container(space())
+                            .width(Length::FillPortion(position(self.media.dur).max(1)))
+                            .height(6)
+                            .style(|theme: &Theme| {
+                                container::Style::default().background(theme.palette().primary)
+                            }),
+                        space().width(Length::FillPortion(
+                            position(self.input_length - self.end).max(1)
+                        ))
+                    ],
+                    self.waveform.clone().map(|handle| {
+                        Image::<image::Handle>::new(handle)
+                            .width(Length::Fill)
+                            .height(Length::Fill)
+                    })
+                ]
+                .spacing(10)
+            ]
+        } else {
+            row![]
+        };
+
+        let status_display = if !self.error.is_empty() {
             row![text(&self.error).style(text::danger)]
         } else if !self.status.is_empty() {
             row![text(&self.status).style(text::primary)]
         } else {
             row![]
+        }
+        .push(self.suggest_timestamp_fix.then(|| {
+            button("retry with fixed timestamps").on_press(Message::RetryWithTimestampFix)
+        }))
+        .push(self.eta.map(|eta| text(format_eta(eta))))
+        .spacing(10);
+
+        let console = self.show_console.then(|| {
+            scrollable(column(
+                self.console
+                    .iter()
+                    .map(|line| text(line).font(Font::MONOSPACE).size(12).into()),
+            ))
+            .anchor_bottom()
+            .width(Length::Fill)
+            .height(150)
+        });
+
+        let sheet_field = |value: u32, on_input: fn(String) -> Message| {
+            text_input("", &value.to_string())
+                .on_input(on_input)
+                .width(60)
+        };
+        let sheet_row = (!self.input_info.is_audio_only()).then(|| {
+            row![
+                text("Contact sheet of the whole input:"),
+                sheet_field(self.sheet.rows, Message::SheetRowsChange),
+                text("rows of"),
+                sheet_field(self.sheet.columns, Message::SheetColumnsChange),
+                text("frames,"),
+                sheet_field(self.sheet.thumb_width, Message::SheetWidthChange),
+                text("px wide"),
+                button(if self.creating_sheet {
+                    "creating..."
+                } else {
+                    "create"
+                })
+                .on_press_maybe(self.allowed(Message::CreateSheet))
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let stills_row = (!self.input_info.is_audio_only()).then(|| {
+            row![
+                text("Stills across the range, every"),
+                text_input(
+                    "",
+                    &self
+                        .stills
+                        .every
+                        .map_or(String::new(), |every| every.to_string())
+                )
+                .on_input(Message::StillsEveryChange)
+                .width(60),
+                text("frames (empty for every keyframe), at most"),
+                sheet_field(self.stills.max_count, Message::StillsMaxChange),
+                text("as"),
+                pick_list(
+                    StillFormat::ALL,
+                    Some(self.stills.format),
+                    Message::StillsFormatChange
+                ),
+                button(if self.exporting_stills {
+                    "exporting..."
+                } else {
+                    "export"
+                })
+                .on_press_maybe(self.allowed(Message::ExportStills))
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)
+        });
+
+        let metadata_row = row![
+            text("Metadata only (rewrites the whole input):"),
+            text_input("title, empty to keep", &self.metadata_title)
+                .on_input(Message::MetadataTitleChange)
+                .width(250),
+            text("Rotation (clockwise):"),
+            pick_list(
+                MetadataEdit::ROTATIONS,
+                self.metadata_rotation,
+                Message::MetadataRotationChange
+            )
+            .placeholder("keep"),
+            button(if self.editing_metadata {
+                "rewriting..."
+            } else {
+                "rewrite input"
+            })
+            .on_press_maybe(
+                (self.input_exists
+                    && !is_network_url(&self.media.input)
+                    && !self.editing_metadata
+                    && (!self.metadata_title.is_empty() || self.metadata_rotation.is_some()))
+                .then_some(Message::EditMetadata)
+            )
+            .style(button::secondary)
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let sample_row = row![
+            text("Sample the first"),
+            text_input("", &self.sample_length.to_string())
+                .on_input(Message::SampleLengthChange)
+                .width(60),
+            text("seconds with these settings:"),
+            button(if self.creating_sample {
+                "encoding..."
+            } else {
+                "encode and play"
+            })
+            .on_press_maybe(
+                (self.input_exists && !self.creating_sample && self.sample_length > 0.0)
+                    .then_some(Message::CreateSample)
+            )
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center);
+
+        let instantiate_button = button("Instantiate!").on_press(Message::Instantiate);
+        let queue_button = button("add to queue")
+            .on_press_maybe(self.allowed(Message::AddToQueue))
+            .style(button::secondary);
+        let keep_open_checkbox = checkbox(self.keep_open)
+            .on_toggle(|_| Message::ToggleKeepOpen)
+            .label("keep open when done");
+        let verify_output_checkbox = checkbox(self.verify_output)
+            .on_toggle(|_| Message::ToggleVerifyOutput)
+            .label("verify output");
+        let keep_mtime_checkbox = checkbox(self.keep_mtime)
+            .on_toggle(|_| Message::ToggleKeepMtime)
+            .label("keep modification time");
+        let create_output_dirs_checkbox = checkbox(self.settings.create_output_dirs)
+            .on_toggle(|_| Message::ToggleCreateOutputDirs)
+            .label("create missing folders");
+        let post_export_field = text_input(
+            "scp {output} host:clips/",
+            &self.settings.post_export_command,
+        )
+        .on_input(Message::PostExportCommandChange)
+        .width(300);
+        let post_export_error = self
+            .post_export_error
+            .as_ref()
+            .map(|e| text(format!("invalid command: {e}")).style(text::danger));
+        // disabled until there is a finished output to show
+        let reveal_button = button("reveal output").on_press_maybe(
+            self.last_output
+                .as_ref()
+                .is_some_and(|path| path.exists())
+                .then_some(Message::RevealOutput),
+        );
+        // the clamped range, since the numbers are only clamped once typing pauses
+        let (start, end) = self.clamped_range();
+        let duration_string = format!("Selected: {}", format_duration(secs(end - start)));
+
+        let palette = self.palette.as_ref().map(|query| {
+            column![
+                text_input("type an action, Enter runs the first match", query)
+                    .id(PALETTE_ID)
+                    .on_input(Message::PaletteChange)
+                    .on_submit(Message::PaletteRun(0))
+            ]
+            .extend(
+                self.palette_matches(query)
+                    .into_iter()
+                    .enumerate()
+                    .take(8)
+                    .map(|(i, (name, _))| {
+                        button(text(name))
+                            .on_press(Message::PaletteRun(i))
+                            .style(if i == 0 {
+                                button::primary
+                            } else {
+                                button::secondary
+                            })
+                            .width(Length::Fill)
+                            .into()
+                    }),
+            )
+            .spacing(5)
+        });
+
+        let truncation_warning = self.input_info.readable_length.map(|readable| {
+            text(format!(
+                "the input claims to be {} long but can't be read past {}, it may be a partial download",
+                format_duration(self.input_info.length),
+                format_duration(readable)
+            ))
+            .style(text::warning)
+        });
+
+        let range_field = text_input("00:01:13,500 --> 00:02:01,000", &self.range_field)
+            .on_input(Message::RangeChange)
+            .on_paste(Message::RangePasted)
+            .on_submit(Message::RangeSubmitted)
+            .width(300);
+        let range_error = self
+            .range_error
+            .as_ref()
+            .map(|e| text(format!("invalid range: {e}")).style(text::danger));
+
+        // the sliders would have empty or inverted ranges without a duration to trim
+        #[rustfmt::skip]
+        let trim: Element<Message> = if self.has_duration() {
+            column![
+                row![text(format!("Start ({unit}):")).width(120), start_field, start_timecode, start_reset, start_slider]
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+
+                row![text(format!("End ({unit}):")).width(120), end_field, resolved_end, end_timecode, end_reset, end_slider]
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+
+                row![text("Or paste a range:"), range_field, text("(enter to apply)").style(text::secondary)]
+                    .push(range_error)
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+            ]
+            .spacing(20)
+            .into()
+        } else {
+            container(text("load a file with a duration to trim it").style(text::secondary))
+                .padding(10)
+                .into()
+        };
+
+        let advanced = self.settings.advanced_view;
+        let advanced_checkbox = checkbox(advanced)
+            .on_toggle(|_| Message::ToggleAdvancedView)
+            .label("advanced settings");
+
+        // everything past the input, the range, the streams, the output and exporting
+        // only shows in the advanced view
+        #[rustfmt::skip]
+        let content: Element<Message> = column![
+            palette,
+
+            restorable_queue,
+
+            previous_output,
+
+            row![input_field, input_picker, advanced_checkbox]
+                .spacing(10)
+                .align_y(Vertical::Center),
+
+            advanced.then(|| row![text("Input options:"), input_args_field, text("(enter to re-probe)").style(text::secondary)]
+                .push(input_args_error)
+                .spacing(10)
+                .align_y(Vertical::Center)),
+
+            truncation_warning,
+
+            trim,
+
+            chapters,
+
+            advanced.then_some(stream_presets),
+
+            row![
+                video_checkbox,
+                row![audio_checkbox, play_audio_button, loudness_text]
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+                subs_checkbox,
+                extra_streams_checkbox
+            ]
+                .spacing(100)
+                .align_y(Vertical::Center),
+
+            audio_offset_row.filter(|_| advanced),
+
+            silence_row.filter(|_| advanced),
+
+            subtitle_row.filter(|_| advanced),
+
+            advanced.then_some(default_streams),
+
+            attachments.filter(|_| advanced),
+
+            // none of these do anything without video
+            (advanced && !self.input_info.is_audio_only()).then(|| column![
+                row![
+                    text("Video codec:"),
+                    video_codec_list,
+                    text("Pixel format:"),
+                    pix_fmt_list,
+                    source_pix_fmt
+                ]
+                .spacing(10)
+                .align_y(Vertical::Center),
+
+                crf_row,
+
+                bit_depth_warning,
+
+                alpha_warning,
+
+                codec_warning,
+
+                encoder_fallbacks,
+
+                row![text("Compare:"), compare_field, compare_picker, compare_layout_list]
+                    .push(compare_warning)
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+
+                watermark_row,
+
+                pad_row,
+
+                row![text("Keyframe interval (seconds):"), keyframe_field, force_keyframes_checkbox]
+                    .push(keyframe_warning)
+                    .spacing(10)
+                    .align_y(Vertical::Center),
+
+                row![text("Crop:"), crop_field, detect_crop_button]
+                    .push(suggested_crop)
+                    .push(crop_warning)
+                    .spacing(10)
+                    .align_y(Vertical::Center)
+            ]
+                .spacing(20)),
+
+            row![output_field]
+                .push(output_generated_tag)
+                .push(container_list)
+                .push(text("if it exists:"))
+                .push(output_policy_list)
+                .push(advanced.then(|| row![mp4_layout, low_priority_checkbox].spacing(10).align_y(Vertical::Center)))
+                .push(output_picker)
+                .spacing(10)
+                .align_y(Vertical::Center),
+
+            advanced.then_some(extra_outputs),
+
+            advanced.then(|| row![
+                text("Split into"),
+                split_parts_field,
+                text("equal parts, or parts of at most"),
+                split_max_field,
+                text("seconds"),
+                copy_ts_checkbox,
+                fix_timestamps_checkbox,
+                shortest_checkbox
+            ]
+            .spacing(10)
+            .align_y(Vertical::Center)),
+
+            advanced.then(|| row![
+                frame_mode_checkbox,
+                percent_mode_checkbox,
+                exact_preview_checkbox,
+                proxy_checkbox,
+                text("Preview size:"),
+                preview_size_list,
+                histogram_checkbox,
+                decode_time_checkbox,
+                decode_time,
+                frame_times_checkbox,
+                checkbox(self.show_scrub).on_toggle(|_| Message::ToggleScrub).label("scrub"),
+                scroll_step_field,
+                history_checkbox,
+                history_button
+            ]
+                .spacing(20)
+                .align_y(Vertical::Center)
+                .wrap()),
+
+            sheet_row.filter(|_| advanced),
+
+            stills_row.filter(|_| advanced),
+
+            advanced.then_some(sample_row),
+
+            advanced.then_some(metadata_row),
+
+            preview_offset_row.filter(|_| advanced),
+
+            scrub_row,
+
+            preview_scanning,
+
+            preview_row,
+
+            frame_times,
+
+            status_display,
+
+            console,
+
+            advanced.then(|| row![text("After exporting, run:"), post_export_field, text("({output} is the output path)").style(text::secondary)]
+                .push(post_export_error)
+                .spacing(10)
+                .align_y(Vertical::Center)),
+
+            row![
+                text("Press Shift-Enter, or:"),
+                instantiate_button,
+                queue_button,
+                text(duration_string)
+            ]
+            .push(advanced.then(|| row![
+                keep_open_checkbox,
+                verify_output_checkbox,
+                keep_mtime_checkbox,
+                create_output_dirs_checkbox
+            ]
+                .spacing(10)
+                .align_y(Vertical::Center)))
+            .push(reveal_button)
+            .push(advanced.then(|| row![
+                button("save job").on_press(Message::SaveJob).style(button::secondary),
+                button("load job").on_press(Message::LoadJob).style(button::secondary),
+                button("queue these settings for other files").on_press(Message::QueueSettingsForFiles).style(button::secondary)
+            ]
+                .spacing(10)))
+            .spacing(10)
+            .align_y(Vertical::Center),
+
+            queue
+        ]
+        .spacing(20)
+        .align_x(Horizontal::Center)
+        .into();
+
+        if self.confirming_queue {
+            return modal(content, self.queue_summary());
+        }
+        if !self.processing {
+            return content;
+        }
+
+        let overlay = container(
+            column![
+                text(format!("Creating {}", self.media.output)),
+                text(self.eta.map_or(self.status.clone(), format_eta)).style(text::primary),
+                progress_bar(0.0..=1.0, self.progress as f32)
+                    .length(300)
+                    .girth(10),
+                button("cancel")
+                    .on_press(Message::CancelInstantiate)
+                    .style(button::danger)
+            ]
+            .spacing(10)
+            .align_x(Horizontal::Center),
+        )
+        .padding(20)
+        .style(container::rounded_box);
+
+        modal(content, overlay)
+    }
+
+    /// every job the queue is about to run, with where it writes and what it does
+    fn queue_summary(&self) -> Element<'_, Message> {
+        let jobs = self.queue.runnable().map(job_media).collect::<Vec<_>>();
+
+        let rows = jobs.iter().map(|media| {
+            // the same checks the run starts with, so a job that would fail says so now
+            let mut resolved = media.clone();
+            let (output, warning) = match resolved.resolve_output() {
+                Ok(()) => {
+                    let output = resolved.first_output();
+                    let overwrites =
+                        media.output_policy == OutputPolicy::Overwrite && output.exists();
+                    (
+                        output,
+                        overwrites.then(|| "overwrites the existing file".to_string()),
+                    )
+                }
+                Err(e) => (resolved.first_output(), Some(format!("will fail: {e}"))),
+            };
+
+            column![
+                text(format!("{} -> {}", media.input, output.display())),
+                text(describe_job(media)).style(text::secondary)
+            ]
+            .push(warning.map(|warning| text(warning).style(text::warning)))
+            .spacing(2)
+            .into()
+        });
+
+        container(
+            column![
+                text(format!("Run {} queued jobs?", jobs.len())),
+                scrollable(column(rows).spacing(10)).height(Length::Shrink),
+                row![
+                    button("run").on_press(Message::ConfirmRunQueue),
+                    button("cancel")
+                        .on_press(Message::CancelRunQueue)
+                        .style(button::secondary)
+                ]
+                .spacing(10)
+            ]
+            .spacing(10)
+            .align_x(Horizontal::Center)
+            .max_width(800),
+        )
+        .padding(20)
+        .style(container::rounded_box)
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            event::listen_with(|event, status, _| Some(Message::Event(event, status))),
+            log::subscription().map(Message::Logged),
+        ])
+    }
+
+    fn check_inputs(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+
+        // a change to the range stays pending until the input settles,
+        // so its previews are made from the new input instead of being dropped
+        if self.number_changed && !self.input_changed {
+            self.clamp_numbers();
+            tasks.push(self.create_preview_images());
+            tasks.push(self.measure_loudness());
+            if self.keyframes_are_stale() {
+                tasks.push(self.rescan_keyframes());
+            }
+
+            self.number_changed = false;
+        }
+        // a path that doesn't exist yet is most likely still being typed, so it stays pending quietly
+        if self.input_changed && self.input_exists {
+            tasks.push(self.probe_input());
+            self.input_changed = false;
+        } else if self.media.output.is_empty() && !self.output_is_generated {
+            tasks.push(self.generate_output_path());
+        }
+
+        Task::batch(tasks)
+    }
+
+    /// the frame rate and the input's length in seconds, when the time fields count in frames or percent
+    fn field_units(&self) -> (Option<f64>, Option<f64>) {
+        let fps = self.input_info.fps.filter(|_| self.frame_mode);
+        let length =
+            Some(secs(self.input_length)).filter(|length| self.percent_mode && *length > 0.0);
+        (fps, length)
+    }
+
+    /// reads a time field in whatever it counts in, see `field_units`
+    fn time_from_field(&self, str: &str) -> i64 {
+        let (fps, length) = self.field_units();
+        micros(match length {
+            Some(length) => secs_from_percent(parse_time(str), length, self.input_info.fps),
+            None => parse_time(str) / fps.unwrap_or(1.0),
+        })
+    }
+
+    /// saves the settings once typing in a settings field pauses, instead of on every keystroke
+    fn save_settings_later(&mut self) -> Task<Message> {
+        let (task, handle) = Task::perform(smol::Timer::after(SETTINGS_DEBOUNCE), |_| {
+            Message::SaveSettings
+        })
+        .abortable();
+        if let Some(extra_handle) = self.settings_save_task_handle.replace(handle) {
+            extra_handle.abort();
+        }
+
+        task
+    }
+
+    /// whether an input with something to trim is loaded
+    fn has_duration(&self) -> bool {
+        self.input_length > 0
+    }
+
+    fn clamp_numbers(&mut self) {
+        (self.media.start, self.end) = self.clamped_range();
+        self.media.dur = self.end - self.media.start;
+    }
+
+    /// how many microseconds a turn of the mouse wheel moves a time slider, counting in notches.
+    /// holding shift makes it ten times coarser, and ctrl ten times finer
+    fn scroll_offset(&self, delta: mouse::ScrollDelta) -> i64 {
+        let notches = match delta {
+            mouse::ScrollDelta::Lines { y, .. } => f64::from(y),
+            mouse::ScrollDelta::Pixels { y, .. } => f64::from(y) / SCROLL_PIXELS_PER_NOTCH,
+        };
+
+        let step = if self.modifiers.shift() {
+            self.settings.scroll_step * 10.0
+        } else if self.modifiers.command() {
+            self.settings.scroll_step / 10.0
+        } else {
+            self.settings.scroll_step
+        };
+
+        micros(notches * step)
+    }
+
+    /// a start that keeps `MIN_GAP` before the end, so moving it never drags the end along.
+    /// an input shorter than the gap can still start at 0
+    fn capped_start(&self, start: i64) -> i64 {
+        let (_, end) = self.clamped_range();
+        start.min((end - MIN_GAP).max(0))
+    }
+
+    /// an end that keeps `MIN_GAP` after the start, up to the end of the input
+    fn capped_end(&self, end: i64) -> i64 {
+        end.max((self.media.start + MIN_GAP).min(self.input_length))
+    }
+
+    /// the start and duration as they will be once clamped into the input, see `clamped_range`
+    fn clamped_span(&self) -> (i64, i64) {
+        let (start, end) = self.clamped_range();
+        (start, end - start)
+    }
+
+    /// the start and end as they will be once clamped into the input
+    fn clamped_range(&self) -> (i64, i64) {
+        let (mut start, mut end) = (self.media.start, self.end);
+
+        // a negative end counts back from the end of the input, like -5 for 5 seconds before it
+        if end < 0 {
+            end = (end + self.input_length).max(0);
+        }
+
+        if start < 0 {
+            start = 0;
+        }
+
+        if end > self.input_length {
+            end = self.input_length;
+        }
+
+        if start > end {
+            start = end;
+        }
+
+        if end < start {
+            end = start;
+        }
+
+        (start, end)
+    }
+
+    /// probes the input in the background, where `InputProbed` picks up the result
+    fn probe_input(&mut self) -> Task<Message> {
+        if let Some(handle) = self.probe_task_handle.take() {
+            handle.abort();
+        }
+        self.current_scrub_preview = None;
+        self.scrub_position = 0.0;
+        self.last_scrub_preview_hash = 0;
+        self.scrub_decoding = false;
+        self.scrub_pending = false;
+
+        let input = self.media.input.clone();
+        let (task, handle) = Task::perform(probe(self.media.clone()), move |result| {
+            Message::InputProbed(input.clone(), result)
+        })
+        .abortable();
+        self.probe_task_handle = Some(handle);
+
+        task
+    }
+
+    /// takes over what probing learned about the input
+    fn apply_probe(&mut self, probed: Media, info: InputInfo) {
+        self.media.use_video = probed.use_video;
+        self.media.use_audio = probed.use_audio;
+        self.media.use_subs = probed.use_subs;
+        self.media.use_extra_streams = probed.use_extra_streams;
+        self.media.fps = probed.fps;
+        self.media.video_stream = probed.video_stream;
+        self.media.source_video_codec = probed.source_video_codec;
+        self.media.source_audio_codec = probed.source_audio_codec;
+        self.input_info = info;
+
+        self.settings.default_streams.apply(&mut self.media);
+        self.input_length = micros(self.input_info.length);
+
+        // Set the end to the duration of the video
+        self.end = self.input_length;
+        self.media.dur = self.end - self.media.start;
+    }
+
+    /// loads everything made from a newly probed input
+    fn update_from_input(&mut self) -> Task<Message> {
+        let mut tasks = Vec::new();
+        // Generate a template output path if there is none from user input
+        if self.media.output.is_empty() || self.output_is_generated {
+            tasks.push(self.generate_output_path());
+        }
+        tasks.push(self.create_proxy());
+        tasks.push(self.scan_keyframes());
+        // these cover any change to the range that waited for the input
+        self.number_changed = false;
+        tasks.push(self.create_preview_images());
+        tasks.push(self.measure_loudness());
+        tasks.push(self.check_readable_length());
+        Task::batch(tasks)
+    }
+
+    /// checks how far the input can be read in the background, which reads through its tail
+    fn check_readable_length(&self) -> Task<Message> {
+        let media = self.media.clone();
+        let length = self.input_info.length;
+        Task::perform(
+            smol::unblock(move || (media.readable_length(length), media.input)),
+            |(readable, input)| Message::LoadedReadableLength(input, readable),
+        )
+    }
+
+    /// loads the input of a job spec, whose settings replace the probed ones once it's probed,
+    /// since probing turns on every stream the input has
+    fn apply_job(&mut self, job: Media) -> Task<Message> {
+        self.media.input = job.input.clone();
+        self.input_exists = is_network_url(&job.input) || Path::new(&job.input).exists();
+        if !self.input_exists {
+            self.error = format!("the job's input '{}' doesn't exist", job.input);
+            return Task::none();
+        }
+
+        self.input_changed = false;
+        self.pending_job = Some(job);
+        self.probe_input()
+    }
+
+    /// replaces the settings with a job's, after its input is probed
+    fn finish_job(&mut self, job: Media) -> Task<Message> {
+        self.media = with_probed(job, std::mem::take(&mut self.media));
+        self.end = self.media.start + self.media.dur;
+        // a job without an output gets one generated for its input
+        self.output_is_generated = self.media.output.is_empty();
+        self.previous_output = None;
+
+        // the fields that hold partial input
+        self.input_args_field = self.media.input_args.join(" ");
+        self.input_args_error = None;
+        self.crop_field = self
+            .media
+            .crop
+            .map(|crop| crop.to_string())
+            .unwrap_or_default();
+        self.keyframe_field = self
+            .media
+            .keyframe_interval
+            .map(|interval| interval.to_string())
+            .unwrap_or_default();
+        (self.compare_input, self.compare_layout) = self
+            .media
+            .compare
+            .as_ref()
+            .map(|compare| (compare.input.clone(), compare.layout))
+            .unwrap_or_default();
+        if let Some(padding) = self.media.padding {
+            self.pad_field = padding.length.to_string();
+            (self.pad_placement, self.pad_fill) = (padding.placement, padding.fill);
+        } else {
+            self.pad_field.clear();
+        }
+
+        self.number_changed = true;
+        let mut tasks = vec![
+            self.check_inputs(),
+            self.create_proxy(),
+            self.scan_keyframes(),
+        ];
+        if self.output_is_generated {
+            tasks.push(self.generate_output_path());
+        }
+        Task::batch(tasks)
+    }
+
+    /// every action the command palette offers, dispatching the same messages as the buttons
+    fn actions(&self) -> Vec<(&'static str, Message)> {
+        vec![
+            ("open input file", Message::PickInput),
+            ("pick output folder", Message::PickOutput),
+            ("export", Message::Instantiate),
+            ("add to queue", Message::AddToQueue),
+            ("run the queue", Message::RunQueue),
+            ("reset start", Message::EagerStartChange(0)),
+            ("reset end", Message::EagerEndChange(self.input_length)),
+            ("swap start and end", Message::SwapBoundaries),
+            ("toggle video", Message::ToggleVideo),
+            ("toggle audio", Message::ToggleAudio),
+            ("toggle subtitles", Message::ToggleSubs),
+            ("toggle extra streams", Message::ToggleExtraStreams),
+            ("toggle exact preview", Message::ToggleExactPreview),
+            ("toggle histogram", Message::ToggleHistogram),
+            ("toggle scrubbing", Message::ToggleScrub),
+            ("toggle frame numbers", Message::ToggleFrameMode),
+            ("toggle percentages", Message::TogglePercentMode),
+            ("toggle console", Message::ToggleConsole),
+            ("toggle keep open", Message::ToggleKeepOpen),
+            ("detect crop", Message::DetectCrop),
+            ("create contact sheet", Message::CreateSheet),
+            ("export stills across the range", Message::ExportStills),
+            ("open history", Message::OpenHistory),
+            ("reveal output", Message::RevealOutput),
+            ("save job spec", Message::SaveJob),
+            ("load job spec", Message::LoadJob),
+            (
+                "queue these settings for other files",
+                Message::QueueSettingsForFiles,
+            ),
+            ("toggle advanced settings", Message::ToggleAdvancedView),
+        ]
+        .into_iter()
+        .filter(|(_, message)| self.allows(message))
+        .collect()
+    }
+
+    /// whether an action can run now, the same as whether its widget is enabled
+    fn allows(&self, message: &Message) -> bool {
+        match message {
+            Message::ToggleFrameMode => self.input_info.fps.is_some(),
+            Message::TogglePercentMode => self.has_duration(),
+            Message::DetectCrop => {
+                self.media.use_video && !self.detecting_crop && self.input_exists
+            }
+            Message::CreateSheet => {
+                self.input_exists && !self.creating_sheet && !self.input_info.is_audio_only()
+            }
+            Message::ExportStills => {
+                self.input_exists && !self.exporting_stills && !self.input_info.is_audio_only()
+            }
+            Message::AddToQueue => self.input_exists,
+            _ => true,
+        }
+    }
+
+    /// the message, if its action can run now
+    fn allowed(&self, message: Message) -> Option<Message> {
+        self.allows(&message).then_some(message)
+    }
+
+    /// the actions whose names contain the query's characters in order
+    fn palette_matches(&self, query: &str) -> Vec<(&'static str, Message)> {
+        let query = query.to_lowercase();
+        self.actions()
+            .into_iter()
+            .filter(|(name, _)| {
+                let mut chars = name.chars();
+                query.chars().all(|c| chars.any(|name_c| name_c == c))
+            })
+            .collect()
+    }
+
+    fn update_padding(&mut self) {
+        self.media.padding = self.pad_field.parse().ok().map(|length| Padding {
+            length,
+            placement: self.pad_placement,
+            fill: self.pad_fill,
+        });
+    }
+
+    fn update_compare(&mut self) {
+        self.media.compare = (!self.compare_input.is_empty()).then(|| Compare {
+            input: self.compare_input.clone(),
+            layout: self.compare_layout,
+        });
+    }
+
+    /// the frame's timestamp, and its number when the frame rate is known
+    fn describe_frame_time(&self, frame_time: Option<FrameTime>) -> String {
+        match (frame_time, self.input_info.fps) {
+            (Some(time), Some(fps)) => format!(
+                "pts {} (frame {}, {:.3}s)",
+                time.pts,
+                time.frame(fps),
+                time.secs
+            ),
+            (Some(time), None) => format!("pts {} ({:.3}s)", time.pts, time.secs),
+            (None, _) => "-".to_string(),
+        }
+    }
+
+    fn log_frame_time(&self, boundary: &str, frame_time: Option<FrameTime>) {
+        if self.show_frame_times {
+            log!(
+                "{boundary} preview shows {}",
+                self.describe_frame_time(frame_time)
+            );
+        }
+    }
+
+    /// the container outputs are written in, which is the one the codec needs before the picked one
+    fn output_container(&self) -> Option<Container> {
+        self.media.video_codec.container().or(self.container)
+    }
+
+    /// moves the output into `container`, or back into the input's container when None,
+    /// then fits the codec and the streams to it
+    fn apply_format(&mut self, container: Option<Container>) {
+        let extension =
+            container.map_or_else(|| self.input_extension(), |container| container.extension());
+        if !self.media.output.is_empty() {
+            self.media.output = Path::new(&self.media.output)
+                .with_extension(extension)
+                .to_string_lossy()
+                .into_owned();
+        }
+
+        let codec = self.media.codec_for_output();
+        if codec != self.media.video_codec {
+            self.status = format!(
+                "Switched the video codec to {codec}, since {extension} can't hold it as it is"
+            );
+            self.media.video_codec = codec;
+        }
+        // a GIF only holds video
+        if Container::of(&self.media.output) == Some(Container::Gif) {
+            self.media.use_audio = false;
+            self.media.use_subs = false;
+            self.media.use_extra_streams = false;
+        }
+    }
+
+    /// the extension an output goes back to when it doesn't need a container of its own,
+    /// which is the input's unless that one only takes its own codecs
+    fn input_extension(&self) -> &'static str {
+        match Container::of(&self.media.input) {
+            Some(Container::Gif | Container::Webm) | None => Container::Mkv.extension(),
+            Some(container) => container.extension(),
+        }
+    }
+
+    fn generate_output_path(&mut self) -> Task<Message> {
+        let input_path = PathBuf::from(&self.media.input);
+        let version = self.media.output_policy == OutputPolicy::Version;
+        let extension = self
+            .output_container()
+            .map(|container| container.extension());
+
+        Task::perform(output_path_for(input_path, extension, version), |path| {
+            Message::OutputChange(
+                path.into_os_string().into_string().unwrap_or_default(),
+                true,
+            )
+        })
+    }
+
+    /// the encoders to fall back on that this build has
+    fn fallbacks(&self) -> Vec<VideoCodec> {
+        self.settings
+            .encoder_fallbacks
+            .iter()
+            .filter(|codec| self.available_codecs.contains(codec))
+            .copied()
+            .collect()
+    }
+
+    /// seconds of output written in this run of the queue, and how many it will write in all,
+    /// counting the jobs done, the running one and the ones still to run
+    fn queue_secs(&self) -> (f64, f64) {
+        let running = self
+            .queue
+            .running()
+            .map_or(0.0, |index| self.queue.jobs[index].media.output_dur());
+        let remaining = self
+            .queue
+            .runnable()
+            .map(|job| job.media.output_dur())
+            .sum::<f64>();
+        (
+            self.queue_done + running * self.queue_progress,
+            self.queue_done + running + remaining,
+        )
+    }
+
+    /// adds a job to the end of the queue, unless it can't run or would write over another job
+    fn queue_job(&mut self, media: Media) {
+        if let Err(e) = media.validate() {
+            self.error = format!("can't queue {}: {e}", media.input);
+        } else if self
+            .queue
+            .jobs
+            .iter()
+            .any(|job| job.media.output == media.output)
+        {
+            self.error = format!("a queued job already writes to {}", media.output);
+        } else {
+            self.queue.jobs.push(QueuedJob {
+                media,
+                state: JobState::Pending,
+            });
+            self.save_queue();
+        }
+    }
+
+    fn save_queue(&self) {
+        if let Err(e) = self.queue.save() {
+            log!("failed to save the queue: {e}");
+        }
+    }
+
+    /// starts the next queued job, unless one is already running.
+    /// each job's progress is reported, and the one after it starts once it's done
+    fn run_next_job(&mut self) -> Task<Message> {
+        if self.queue_task_handle.is_some() {
+            return Task::none();
+        }
+        let Some(job) = self.queue.next_job() else {
+            return Task::none();
+        };
+
+        let media = job_media(job);
+        job.state = JobState::Running;
+        self.save_queue();
+
+        let fallbacks = self.fallbacks();
+        let create_output_dirs = self.settings.create_output_dirs;
+        let log_history = self.log_history;
+        let (task, handle) = Task::stream(iced::stream::channel(
+            100,
+            async move |mut output: mpsc::Sender<Message>| {
+                let mut progress_output = output.clone();
+                let report = |progress| {
+                    // dropping a report is harmless, the next one supersedes it
+                    let _ = progress_output.try_send(Message::QueueProgress(progress));
+                };
+
+                let result = run_job(media.clone(), &fallbacks, create_output_dirs, report).await;
+                if log_history && let Err(e) = append_history(&HistoryEntry::new(&media, &result)) {
+                    log!("failed to write to the history log: {e}");
+                }
+
+                let _ = output.send(Message::QueuedJobFinished(result)).await;
+            },
+        ))
+        .abortable();
+        self.queue_task_handle = Some(handle);
+
+        task
+    }
+
+    fn instantiate(&self) -> Task<Message> {
+        // run with the range it will be trimmed to, not what is still being typed
+        let (start, dur) = self.clamped_span();
+        let mut media = Media {
+            start,
+            dur,
+            ..self.media.clone()
+        };
+        let output_is_generated = self.output_is_generated;
+        let previous_output = self.media.output.clone();
+        let log_history = self.log_history;
+        let verify_output = self.verify_output;
+        let keep_mtime = self.keep_mtime;
+        let create_output_dirs = self.settings.create_output_dirs;
+        let post_export_command = self.settings.post_export_command.clone();
+        let fallbacks = self.fallbacks();
+
+        Task::stream(iced::stream::channel(
+            100,
+            async move |mut output: mpsc::Sender<Message>| {
+                let mut progress_output = output.clone();
+                let mut report = |progress| {
+                    // dropping a report is harmless, the next one supersedes it
+                    let _ = progress_output.try_send(Message::Progress(progress));
+                };
+
+                if let Some(dir) = missing_dir(&media.first_output()) {
+                    let result = if create_output_dirs || confirm_create_dir(dir.clone()).await {
+                        create_dir(&dir)
+                    } else {
+                        Err(format!("Not creating the output folder {}", dir.display()))
+                    };
+                    if let Err(e) = result {
+                        let _ = output.send(Message::InstantiateFinished(Err(e))).await;
+                        return;
+                    }
+                }
+
+                if media.output_policy == OutputPolicy::Ask && media.first_output().exists() {
+                    if !confirm_overwrite(media.first_output()).await {
+                        let _ = output
+                            .send(Message::InstantiateFinished(Err(
+                                "Not overwriting the existing output".to_string(),
+                            )))
+                            .await;
+                        return;
+                    }
+                    media.output_policy = OutputPolicy::Overwrite;
+                }
+
+                // resolved here as well, so the output field shows where the output went
+                if let Err(e) = media.resolve_output() {
+                    let _ = output.send(Message::InstantiateFinished(Err(e))).await;
+                    return;
+                }
+                if media.output != previous_output {
+                    let _ = output
+                        .send(Message::OutputChange(
+                            media.output.clone(),
+                            output_is_generated,
+                        ))
+                        .await;
+                }
+
+                let result = media
+                    .clone()
+                    .run_with_fallbacks(&fallbacks, &mut report)
+                    .await
+                    .map(|(codec, finished)| {
+                        let chosen = media.video_codec;
+                        // the history and the checks below go by what actually ran
+                        media.video_codec = codec;
+                        let status = if codec == chosen {
+                            "Finished".to_string()
+                        } else {
+                            format!("Finished with {codec}, since {chosen} failed")
+                        };
+                        (status, finished.non_monotonic_timestamps)
+                    });
+                // offered as a retry, whether or not the output was written
+                let non_monotonic = matches!(
+                    result,
+                    Ok((_, true)) | Err(CreateError::NonMonotonicTimestamps)
+                );
+                if non_monotonic {
+                    let _ = output.send(Message::SuggestTimestampFix).await;
+                }
+                let result = result.map(|(status, non_monotonic)| {
+                    if non_monotonic {
+                        format!(
+                            "{status}, but its timestamps go backwards, so it will likely glitch"
+                        )
+                    } else {
+                        status
+                    }
+                });
+                let mut result = result.map_err(|e| e.to_string());
+
+                // ffmpeg can finish with an extra output left unwritten, so each one is checked
+                if !media.extra_outputs.is_empty()
+                    && let Ok(status) = &result
+                {
+                    let (written, missing): (Vec<_>, Vec<_>) =
+                        media.outputs().into_iter().partition(|path| path.exists());
+                    let names = |paths: Vec<PathBuf>| {
+                        paths
+                            .iter()
+                            .map(|path| path.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    result = if missing.is_empty() {
+                        Ok(format!("{status}, wrote {}", names(written)))
+                    } else {
+                        Err(format!("{status}, but didn't write {}", names(missing)))
+                    };
+                }
+
+                // a URL input has no modification time to copy
+                if keep_mtime && result.is_ok() && Path::new(&media.input).is_file() {
+                    let input = Path::new(&media.input);
+                    for path in media.outputs() {
+                        if let Err(e) = copy_mtime(input, &path) {
+                            log!(
+                                "failed to copy the modification time to {}: {e}",
+                                path.display()
+                            );
+                        }
+                    }
+                }
+
+                // reported as an error so the window stays open to show it
+                if verify_output && let Ok(status) = &result {
+                    result = media
+                        .verify_output()
+                        .map(|()| status.clone())
+                        .map_err(|e| format!("{status}, but the output looks broken: {e}"));
+                }
+
+                if !post_export_command.trim().is_empty()
+                    && let Ok(status) = &result
+                {
+                    for path in media.outputs() {
+                        if let Err(e) = run_post_export(post_export_command.clone(), path).await {
+                            result =
+                                Err(format!("{status}, but the post-export command failed: {e}"));
+                            break;
+                        }
+                    }
+                    if let Ok(status) = &mut result {
+                        status.push_str(", and ran the post-export command");
+                    }
+                }
+
+                if log_history && let Err(e) = append_history(&HistoryEntry::new(&media, &result)) {
+                    log!("failed to write to the history log: {e}");
+                }
+
+                let _ = output.send(Message::InstantiateFinished(result)).await;
+            },
+        ))
+    }
+
+    /// makes a batch of tasks to create start and end preview images
+    /// no effect if use_video is false
+    fn create_preview_images(&mut self) -> Task<Message> {
+        if self.input_info.is_audio_only() {
+            return self.create_waveform();
+        }
+        if !self.media.use_video {
+            return Task::none();
+        }
+
+        // only the previews come from the proxy, the output is still made from the input
+        let input = match &self.proxy {
+            Some(proxy) if self.proxy_ready => proxy.0.to_string_lossy().to_string(),
+            _ => self.media.input.clone(),
+        };
+
+        let start_preview = Preview {
+            seek: (self.media.start + micros(self.preview_offset)).max(0),
+            input: input.clone(),
+            prev_hash: self.last_start_preview_hash,
+            exact: self.exact_preview,
+            before: false,
+            size: self.preview_size,
+        };
+        let end_preview = Preview {
+            seek: self.end_preview_seek(),
+            input,
+            prev_hash: self.last_end_preview_hash,
+            exact: self.exact_preview,
+            before: true,
+            size: self.preview_size,
+        };
+
+        Task::batch([
+            if start_preview == self.last_start_preview {
+                // No need to reload the same image
+                Task::none()
+            } else {
+                self.last_start_preview = start_preview.clone();
+                let (task, handle) = decode_preview(
+                    start_preview,
+                    Message::StartPreviewProgress,
+                    Message::LoadedStartPreview,
+                )
+                .abortable();
+                if let Some(extra_handle) = &self.start_preview_task_handle {
+                    extra_handle.abort();
+                }
+                self.start_preview_task_handle = Some(handle);
+
+                task
+            },
+            if end_preview == self.last_end_preview {
+                // No need to reload the same image
+                Task::none()
+            } else {
+                self.last_end_preview = end_preview.clone();
+                let (task, handle) = decode_preview(
+                    end_preview,
+                    Message::EndPreviewProgress,
+                    Message::LoadedEndPreview,
+                )
+                .abortable();
+                if let Some(extra_handle) = &self.end_preview_task_handle {
+                    extra_handle.abort();
+                }
+                self.end_preview_task_handle = Some(handle);
+
+                task
+            },
+        ])
+    }
+
+    /// decodes the frame at the scrub slider,
+    /// or only remembers to once the one that's decoding is done
+    fn decode_scrub_preview(&mut self) -> Task<Message> {
+        if !self.media.use_video || self.input_info.is_audio_only() {
+            return Task::none();
+        }
+        if self.scrub_decoding {
+            self.scrub_pending = true;
+            return Task::none();
+        }
+
+        let input = match &self.proxy {
+            Some(proxy) if self.proxy_ready => proxy.0.to_string_lossy().to_string(),
+            _ => self.media.input.clone(),
+        };
+        let preview = Preview {
+            seek: micros(self.scrub_position.max(0.0)),
+            input,
+            prev_hash: self.last_scrub_preview_hash,
+            exact: self.exact_preview,
+            before: false,
+            size: self.preview_size,
+        };
+
+        self.scrub_decoding = true;
+        let input = self.media.input.clone();
+        Task::perform(preview.decode_preview_image(|_| {}), move |result| {
+            Message::LoadedScrubPreview(input.clone(), result)
+        })
+    }
+
+    /// where the end preview is decoded from, after the offset
+    fn end_preview_seek(&self) -> i64 {
+        let seek = (self.end + micros(self.preview_offset)).clamp(0, self.input_length.max(0));
+        // seek slightly before the end of the video to get a frame,
+        // by less for a short input so it doesn't skip back over all of it
+        if seek > self.input_length - micros(0.1) {
+            let nudge = END_PREVIEW_NUDGE.min(self.input_length / 4);
+            (seek - nudge).max(0)
+        } else {
+            seek
+        }
+    }
+
+    /// starts encoding a preview proxy of the input in the background, dropping the previous one
+    fn create_proxy(&mut self) -> Task<Message> {
+        if let Some(handle) = self.proxy_task_handle.take() {
+            handle.abort();
+        }
+        self.proxy = None;
+        self.proxy_ready = false;
+
+        let Some(stream) = self.media.video_stream.filter(|_| self.use_proxy) else {
+            return Task::none();
+        };
+
+        let path = proxy_path();
+        self.proxy = Some(TempFile(path.clone()));
+        let proxy = Proxy {
+            input: self.media.input.clone(),
+            stream,
+        };
+
+        let (task, handle) = Task::perform(proxy.create(path), Message::LoadedProxy).abortable();
+        self.proxy_task_handle = Some(handle);
+
+        task
+    }
+
+    /// scans the new input for its keyframes in the background, dropping the previous ones
+    fn scan_keyframes(&mut self) -> Task<Message> {
+        self.keyframes.clear();
+        self.rescan_keyframes()
+    }
+
+    /// scans for the keyframes around the boundaries in the background,
+    /// keeping the previous ones until that's done
+    fn rescan_keyframes(&mut self) -> Task<Message> {
+        if let Some(handle) = self.keyframes_task_handle.take() {
+            handle.abort();
+        }
+
+        let Some(stream) = self.media.video_stream else {
+            return Task::none();
+        };
+        let (start, end) = self.clamped_range();
+        self.keyframes_around = Some((start, end));
+        let probe = KeyframeProbe {
+            input: self.media.input.clone(),
+            input_args: self.media.input_args.clone(),
+            stream,
+            start,
+            end,
+        };
+
+        let (task, handle) = Task::perform(probe.scan(), Message::LoadedKeyframes).abortable();
+        self.keyframes_task_handle = Some(handle);
+
+        task
+    }
+
+    /// whether a boundary moved far enough from where the keyframes were looked for to miss some
+    fn keyframes_are_stale(&self) -> bool {
+        let (start, end) = self.clamped_range();
+        self.keyframes_around
+            .is_none_or(|(around_start, around_end)| {
+                (start - around_start).abs() > KeyframeProbe::WINDOW / 2
+                    || (end - around_end).abs() > KeyframeProbe::WINDOW / 2
+            })
+    }
+
+    /// tick marks at the keyframes inside a slider's range, for seeing where copying cuts exactly.
+    /// left out when there are too many to tell apart
+    fn keyframe_ticks(&self, range: RangeInclusive<i64>) -> Option<Element<'_, Message>> {
+        if self.media.video_codec != VideoCodec::Copy || !self.media.use_video {
+            return None;
+        }
+
+        let (low, high) = (*range.start(), *range.end());
+        let ticks: Vec<i64> = self
+            .keyframes
+            .iter()
+            .copied()
+            .filter(|time| range.contains(time))
+            .collect();
+        if ticks.is_empty() || ticks.len() > MAX_KEYFRAME_TICKS || high <= low {
+            return None;
+        }
+
+        // each tick is preceded by the space since the last one
+        let portion = |time: i64| {
+            ((time as f64 / (high - low) as f64).clamp(0.0, 1.0) * 1000.0).round() as u16
+        };
+        let mut row = row![];
+        let mut last = low;
+        for time in ticks {
+            row =
+                row.push(space().width(Length::FillPortion(portion(time - last))))
+                    .push(container(space()).width(1).height(Length::Fill).style(
+                        |theme: &Theme| {
+                            container::Style::default().background(theme.palette().primary)
+                        },
+                    ));
+            last = time;
+        }
+        row = row.push(space().width(Length::FillPortion(portion(high - last))));
+
+        Some(row.height(16).into())
+    }
+
+    /// renders the selected range's waveform in the background
+    fn create_waveform(&mut self) -> Task<Message> {
+        let (start, dur) = self.clamped_span();
+        let probe = WaveformProbe {
+            input: self.media.input.clone(),
+            start,
+            dur,
+        };
+
+        if probe == self.last_waveform_probe {
+            // No need to render the same range again
+            return Task::none();
+        }
+        self.last_waveform_probe = probe.clone();
+
+        let (task, handle) = Task::perform(probe.render(), Message::LoadedWaveform).abortable();
+        if let Some(extra_handle) = &self.waveform_task_handle {
+            extra_handle.abort();
+        }
+        self.waveform_task_handle = Some(handle);
+
+        task
+    }
+
+    /// measures the loudness of the selected range in the background
+    /// no effect if use_audio is false
+    fn measure_loudness(&mut self) -> Task<Message> {
+        if !self.media.use_audio {
+            return Task::none();
+        }
+
+        let (start, dur) = self.clamped_span();
+        let probe = LoudnessProbe {
+            input: self.media.input.clone(),
+            start,
+            dur,
         };
 
-        let instantiate_button = button("Instantiate!").on_press(Message::Instantiate);
-        let duration_string = format!("Duration: {} seconds", self.media.dur);
+        if probe == self.last_loudness_probe {
+            // No need to measure the same range again
+            return Task::none();
+        }
+        self.last_loudness_probe = probe.clone();
 
-        #[rustfmt::skip]
-        return column![
-            row![input_field, input_picker],
+        let (task, handle) = Task::perform(probe.measure(), Message::LoadedLoudness).abortable();
+        if let Some(extra_handle) = &self.loudness_task_handle {
+            extra_handle.abort();
+        }
+        self.loudness_task_handle = Some(handle);
 
-            row![text("Start time (seconds):  "), start_field, start_slider]
-                .align_y(Vertical::Center),
+        task
+    }
+}
 
-            row![text("End time (seconds):    "), end_field, end_slider]
-                .align_y(Vertical::Center),
+const PALETTE_ID: &str = "palette";
 
-            row![video_checkbox, audio_checkbox, subs_checkbox, extra_streams_checkbox]
-                .spacing(100)
-                .align_y(Vertical::Center),
+/// the audio offset slider goes this many seconds either way
+const AUDIO_OFFSET_RANGE: f64 = 1.0;
 
-            row![output_field, output_picker],
+/// the preview offset slider goes this many seconds either way
+const PREVIEW_OFFSET_RANGE: f64 = 2.0;
 
-            preview_row,
+/// how long typing in the input field has to pause before the input is opened
+const INPUT_DEBOUNCE: Duration = Duration::from_millis(400);
 
-            status_display,
+/// how long typing in a settings field has to pause before the settings are saved
+const SETTINGS_DEBOUNCE: Duration = Duration::from_millis(500);
 
-            row![text("Press Shift-Enter, or:"), instantiate_button, text(duration_string)]
-                .spacing(10)
-                .align_y(Vertical::Center)
-        ]
-        .spacing(20)
-        .align_x(Horizontal::Center)
-        .into();
+/// how many more times probing a network input is tried, waiting twice as long each time
+const NETWORK_RETRIES: u32 = 3;
+const NETWORK_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// more keyframes than this in a slider's range would just fill it in
+const MAX_KEYFRAME_TICKS: usize = 200;
+
+/// touchpads scroll by pixels, this many of which count as one notch of a mouse wheel
+const SCROLL_PIXELS_PER_NOTCH: f64 = 40.0;
+
+/// how many microseconds before the end of the input the end preview is decoded from, at most
+const END_PREVIEW_NUDGE: i64 = 500_000;
+
+/// the sliders default to whole seconds, which is too coarse to pick a frame with
+const SLIDER_STEP: f64 = 0.01;
+
+/// libx264's own default, where the CRF slider starts
+const DEFAULT_CRF: u8 = 23;
+
+/// the shortest range moving a boundary can leave, in microseconds
+const MIN_GAP: i64 = 1_000_000;
+
+/// probes the input on a blocking thread, since opening it can take a while,
+/// retrying network inputs with twice the delay each time
+async fn probe(mut media: Media) -> Result<(Media, InputInfo), String> {
+    let network = is_network_url(&media.input);
+    let mut delay = NETWORK_RETRY_DELAY;
+    let mut retries = 0;
+    loop {
+        let result;
+        (media, result) = smol::unblock(move || {
+            let result = media.update_video_params();
+            (media, result)
+        })
+        .await;
+
+        match result {
+            Err(e) if network && retries < NETWORK_RETRIES => {
+                log!(
+                    "failed to probe '{}', retrying in {delay:?}: {e}",
+                    media.input
+                );
+                smol::Timer::after(delay).await;
+                delay *= 2;
+                retries += 1;
+            }
+            result => return result.map(|info| (media, info)).map_err(|e| e.to_string()),
+        }
     }
+}
 
-    fn subscription(&self) -> Subscription<Message> {
-        event::listen().map(Message::Event)
+/// shows the overlay over the content,
+/// which it covers so nothing underneath can be clicked
+fn modal<'a>(
+    content: impl Into<Element<'a, Message>>,
+    overlay: impl Into<Element<'a, Message>>,
+) -> Element<'a, Message> {
+    stack![
+        content.into(),
+        opaque(center(overlay).style(|_| {
+            container::Style::default().background(Color::from_rgba(0.0, 0.0, 0.0, 0.7))
+        }))
+    ]
+    .into()
+}
+
+/// the output path generated for an input, in another container when there's an `extension`
+async fn output_path_for(input: PathBuf, extension: Option<&str>, version: bool) -> PathBuf {
+    let path = modify_path(input.clone()).await;
+    let path = match extension {
+        Some(extension) => path.with_extension(extension),
+        None => path,
+    };
+    // re-exporting a previous output would otherwise write over the input
+    if (version && path.exists()) || path == input {
+        versioned_path(&path, Path::exists)
+    } else {
+        path
     }
+}
 
-    fn check_inputs(&mut self) -> Task<Message> {
-        let mut tasks = Vec::new();
+/// the settings for another input, which is probed for what it has.
+/// the range and the outputs belong to the old input, so the new one is used whole
+async fn job_for(
+    template: Media,
+    input: PathBuf,
+    extension: Option<&'static str>,
+) -> Result<Media, String> {
+    let media = Media {
+        input: input.to_string_lossy().into_owned(),
+        ..template.clone()
+    };
+    let (probed, info) = probe(media)
+        .await
+        .map_err(|e| format!("failed to inspect input media '{}': {e}", input.display()))?;
 
-        if self.number_changed {
-            self.clamp_numbers();
-            if !self.input_changed {
-                tasks.push(self.create_preview_images());
-            }
+    let version = template.output_policy == OutputPolicy::Version;
+    let output = output_path_for(input, extension, version).await;
+    Ok(Media {
+        input: probed.input.clone(),
+        output: output.to_string_lossy().into_owned(),
+        extra_outputs: Vec::new(),
+        start: 0,
+        dur: micros(info.length),
+        // only the streams both were set to keep and the new input has
+        use_video: template.use_video && probed.use_video,
+        use_audio: template.use_audio && probed.use_audio,
+        use_subs: template.use_subs && probed.use_subs,
+        use_extra_streams: template.use_extra_streams && probed.use_extra_streams,
+        ..with_probed(template, probed)
+    })
+}
 
-            self.number_changed = false;
-        }
-        if self.input_changed {
-            match self.update_from_input() {
-                Err(e) => eprintln!("failed to inspect input media '{}': {e}", self.media.input),
-                Ok(task) => {
-                    tasks.push(task);
-                    tasks.push(self.create_preview_images());
-                }
-            }
+/// the settings a queued job runs with
+fn job_media(job: &QueuedJob) -> Media {
+    let mut media = job.media.clone();
+    // whatever is at the output of an interrupted job is its own partial output
+    if job.state == JobState::Interrupted && media.output_policy == OutputPolicy::Ask {
+        media.output_policy = OutputPolicy::Overwrite;
+    }
+    media
+}
 
-            self.input_changed = false;
-        } else if self.media.output.is_empty() && !self.output_is_generated {
-            tasks.push(self.generate_output_path());
-        }
+/// what a queued job will do in a line, for checking before the queue runs
+fn describe_job(media: &Media) -> String {
+    let streams = [
+        (media.use_video, "video"),
+        (media.use_audio, "audio"),
+        (media.use_subs, "subtitles"),
+        (media.use_extra_streams, "extra streams"),
+    ]
+    .into_iter()
+    .filter_map(|(used, name)| used.then_some(name))
+    .collect::<Vec<_>>();
 
-        Task::batch(tasks)
+    let mut parts = vec![
+        format!(
+            "{} to {}",
+            format_timecode(media.start),
+            format_timecode(media.start + media.dur)
+        ),
+        streams.join(", "),
+    ];
+    if media.use_video {
+        parts.push(
+            match media.crf.filter(|_| media.video_codec.supports_crf()) {
+                Some(crf) => format!("{} at crf {crf}", media.video_codec),
+                None => media.video_codec.to_string(),
+            },
+        );
+    }
+    if media.split != Split::Off {
+        parts.push("split into parts".to_string());
     }
+    if !media.extra_outputs.is_empty() {
+        parts.push(format!("{} extra outputs", media.extra_outputs.len()));
+    }
+    parts.join(", ")
+}
 
-    fn clamp_numbers(&mut self) {
-        if self.media.start < 0.0 {
-            self.media.start = 0.0;
-        }
+/// a job's settings, with what was learned by probing its input, which jobs aren't saved with
+fn with_probed(job: Media, probed: Media) -> Media {
+    Media {
+        fps: probed.fps,
+        video_stream: probed.video_stream,
+        source_video_codec: probed.source_video_codec,
+        source_audio_codec: probed.source_audio_codec,
+        ..job
+    }
+}
 
-        if self.end > self.input_length {
-            self.end = self.input_length;
-        }
+/// runs a queued job, probing its input again first, returning the status to show on success
+async fn run_job(
+    media: Media,
+    fallbacks: &[VideoCodec],
+    create_output_dirs: bool,
+    report: impl FnMut(Progress),
+) -> Result<String, String> {
+    let (probed, _) = probe(media.clone())
+        .await
+        .map_err(|e| format!("failed to inspect input media '{}': {e}", media.input))?;
+    let media = with_probed(media, probed);
 
-        if self.media.start > self.end {
-            self.media.start = self.end;
+    // there's no one to ask while the queue runs
+    if let Some(dir) = missing_dir(&media.first_output()) {
+        if !create_output_dirs {
+            return Err(format!("the output folder {} doesn't exist", dir.display()));
         }
+        create_dir(&dir)?;
+    }
 
-        if self.end < self.media.start {
-            self.end = self.media.start;
-        }
+    let chosen = media.video_codec;
+    let (codec, finished) = media
+        .run_with_fallbacks(fallbacks, report)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut status = if codec == chosen {
+        "Finished".to_string()
+    } else {
+        format!("Finished with {codec}, since {chosen} failed")
+    };
+    if finished.non_monotonic_timestamps {
+        status.push_str(", but its timestamps go backwards, so it will likely glitch");
     }
+    Ok(status)
+}
 
-    fn update_from_input(&mut self) -> Result<Task<Message>, ffmpeg::Error> {
-        if !self.input_exists {
-            eprintln!("input_exists is set to false, not attempting to update from input");
-            return Err(ffmpeg::Error::Unknown);
-        }
+/// runs a future, also returning how long it took
+async fn timed<T>(future: impl Future<Output = T>) -> (T, Duration) {
+    let started = Instant::now();
+    let output = future.await;
+    (output, started.elapsed())
+}
 
-        self.input_length = self.media.update_video_params()?;
+/// decodes a preview in the background, reporting progress while it is slow to find a frame
+fn decode_preview(
+    preview: Preview,
+    on_progress: fn(usize) -> Message,
+    on_loaded: fn(PreviewResult, Duration) -> Message,
+) -> Task<Message> {
+    Task::stream(iced::stream::channel(
+        10,
+        async move |mut output: mpsc::Sender<Message>| {
+            let mut progress_output = output.clone();
+            // decoding blocks, so it runs on its own thread for the reports to get through meanwhile
+            let (result, took) = timed(smol::unblock(move || {
+                smol::block_on(preview.decode_preview_image(|packets| {
+                    // dropping a report is harmless, the next one supersedes it
+                    let _ = progress_output.try_send(on_progress(packets));
+                }))
+            }))
+            .await;
+            let _ = output.send(on_loaded(result, took)).await;
+        },
+    ))
+}
 
-        // Set the end to the duration of the video
-        self.end = self.input_length;
+/// draws the red, green and blue histograms as bars, with a warning when the image is blown out
+fn histogram_view<'a>(histogram: &Histogram) -> Element<'a, Message> {
+    const HEIGHT: f32 = 40.0;
 
-        // Generate a template output path if there is none from user input
-        if self.media.output.is_empty() || self.output_is_generated {
-            Ok(self.generate_output_path())
-        } else {
-            Ok(Task::none())
-        }
+    let channels = histogram.bins.iter().zip([
+        color!(0xff0000, 0.7),
+        color!(0x00ff00, 0.7),
+        color!(0x0000ff, 0.7),
+    ]);
+
+    column(channels.map(|(bins, bar_color)| {
+        let max = bins.iter().max().copied().unwrap_or_default().max(1);
+        row(bins.iter().map(|count| {
+            container(space())
+                .width(Length::Fill)
+                .height(HEIGHT * *count as f32 / max as f32)
+                .style(move |_| container::Style::default().background(bar_color))
+                .into()
+        }))
+        .height(HEIGHT)
+        .align_y(Vertical::Bottom)
+        .into()
+    }))
+    .push(
+        histogram.is_overexposed().then(|| {
+            text(format!("{:.1}% clipped", histogram.clipped * 100.0)).style(text::danger)
+        }),
+    )
+    .width(200)
+    .into()
+}
+
+/// how many speed reports the ETA is averaged over
+const SPEED_SAMPLES: usize = 5;
+
+/// formats a number of seconds as a rough "time left" string
+fn format_eta(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    if secs >= 3600 {
+        format!("~{}h {}m left", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("~{}m left", (secs as f64 / 60.0).round())
+    } else {
+        format!("~{secs}s left")
     }
+}
 
-    fn generate_output_path(&mut self) -> Task<Message> {
-        let input_path = PathBuf::from(&self.media.input);
+/// formats microseconds as a timecode like 01:02:03.040
+fn format_timecode(micros: i64) -> String {
+    let millis = (micros.max(0) as u64 + 500) / 1000;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
 
-        Task::perform(modify_path(input_path), |path| {
-            Message::OutputChange(
-                path.into_os_string().into_string().unwrap_or_default(),
-                true,
-            )
-        })
+/// formats a number of seconds as a short duration like 0:42.5 or 1:02:03.5
+fn format_duration(secs: f64) -> String {
+    let tenths = (secs * 10.0).round() as u64;
+    let (hours, minutes, secs) = (tenths / 36_000, tenths / 600 % 60, tenths % 600);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{:02}.{}", secs / 10, secs % 10)
+    } else {
+        format!("{minutes}:{:02}.{}", secs / 10, secs % 10)
     }
+}
 
-    fn instantiate(&self) -> Task<Message> {
-        Task::perform(self.media.clone().create(), Message::InstantiateFinished)
+/// resolves a percentage of the input's length to seconds,
+/// rounded to the nearest frame when the frame rate is known since a percentage rarely lands on one
+fn secs_from_percent(percent: f64, length: f64, fps: Option<f64>) -> f64 {
+    let secs = percent / 100.0 * length;
+    fps.map_or(secs, |fps| (secs * fps).round() / fps)
+}
+
+/// parses a time in seconds from a text field, falling back to 0 for anything that isn't a finite number
+fn parse_time(str: &str) -> f64 {
+    str.parse::<f64>()
+        .ok()
+        .filter(|val| val.is_finite())
+        .unwrap_or_default()
+}
+
+/// parses a time like 01:13.5, 00:01:13,500 or 73.5 into seconds,
+/// taking a comma for the decimal point like subtitle files do
+fn parse_timecode(str: &str) -> Option<f64> {
+    let str = str.trim().replace(',', ".");
+    let parts: Vec<&str> = str.split(':').collect();
+    if parts.len() > 3 {
+        return None;
     }
 
-    /// makes a batch of tasks to create start and end preview images
-    /// no effect if use_video is false
-    fn create_preview_images(&mut self) -> Task<Message> {
-        if !self.media.use_video {
-            return Task::none();
-        }
+    parts.iter().enumerate().try_fold(0.0, |secs, (i, part)| {
+        let value = part.parse::<f64>().ok()?;
+        // only the seconds can have a fraction
+        let fraction = part.contains('.') && i + 1 < parts.len();
+        (value.is_finite() && value >= 0.0 && !fraction).then_some(secs * 60.0 + value)
+    })
+}
 
-        let start_preview = Preview {
-            seek: (self.media.start * 1_000_000.0).round() as i64,
-            input: self.media.input.clone(),
-            prev_hash: self.last_start_preview_hash,
-        };
-        let end_preview = Preview {
-            seek: // seek slightly before the end of the video to get a frame
-                (if self.end > self.input_length - 0.1 {
-                    self.end - 0.5
-                } else {
-                    self.end
-                } * 1_000_000.0).round() as i64,
-            input: self.media.input.clone(),
-            prev_hash: self.last_end_preview_hash,
-        };
+/// parses a scroll step, which has to be a positive number of seconds
+fn parse_scroll_step(str: &str) -> Option<f64> {
+    str.trim()
+        .parse()
+        .ok()
+        .filter(|step: &f64| step.is_finite() && *step > 0.0)
+}
 
-        Task::batch([
-            if start_preview == self.last_start_preview {
-                // No need to reload the same image
-                Task::none()
-            } else {
-                self.last_start_preview = start_preview.clone();
-                let (task, handle) = Task::perform(
-                    start_preview.decode_preview_image(),
-                    Message::LoadedStartPreview,
-                )
-                .abortable();
-                if let Some(extra_handle) = &self.start_preview_task_handle {
-                    extra_handle.abort();
-                }
-                self.start_preview_task_handle = Some(handle);
+/// parses a minimum silence duration, which has to be a non-negative number of seconds
+fn parse_silence_duration(str: &str) -> Option<f64> {
+    str.trim()
+        .parse()
+        .ok()
+        .filter(|duration: &f64| duration.is_finite() && *duration >= 0.0)
+}
 
-                task
-            },
-            if end_preview == self.last_end_preview {
-                // No need to reload the same image
-                Task::none()
-            } else {
-                self.last_end_preview = end_preview.clone();
-                let (task, handle) = Task::perform(
-                    end_preview.decode_preview_image(),
-                    Message::LoadedEndPreview,
-                )
-                .abortable();
-                if let Some(extra_handle) = &self.end_preview_task_handle {
-                    extra_handle.abort();
+/// parses a pasted pair of times, like "00:01:13,500 --> 00:02:01,000" from a subtitle file or "1:13 - 2:01"
+fn parse_range(str: &str) -> Result<(f64, f64), String> {
+    let (start, end) = ["-->", "\u{2013}", "\u{2014}", "-"]
+        .iter()
+        .find_map(|separator| str.split_once(separator))
+        .ok_or("expected two times separated by --> or -")?;
+    let parse =
+        |time: &str| parse_timecode(time).ok_or_else(|| format!("'{}' isn't a time", time.trim()));
+
+    let (start, end) = (parse(start)?, parse(end)?);
+    if end < start {
+        return Err("it ends before it starts".to_string());
+    }
+    Ok((start, end))
+}
+
+/// how many previews the benchmark decodes at each size, spread evenly across the input
+const BENCHMARK_FRAMES: u32 = 10;
+
+/// decodes previews across the input at every preview size, printing how long they took,
+/// to tell whether a smaller size or a proxy is worth it on this machine
+fn benchmark(input: &str) -> Result<(), String> {
+    ffmpeg::init().map_err(|e| format!("failed to initialize ffmpeg: {e}"))?;
+
+    let mut media = Media {
+        input: input.to_string(),
+        ..Default::default()
+    };
+    let info = media
+        .update_video_params()
+        .map_err(|e| format!("failed to probe '{input}': {e}"))?;
+    if !info.has_video {
+        return Err(format!("'{input}' has no video to decode"));
+    }
+
+    println!(
+        "decoding {BENCHMARK_FRAMES} previews across {:.1} seconds of {input}",
+        info.length
+    );
+    for size in PreviewSize::ALL {
+        let mut total = Duration::ZERO;
+        let mut decoded = 0;
+        for i in 0..BENCHMARK_FRAMES {
+            let seek = info.length * (f64::from(i) + 0.5) / f64::from(BENCHMARK_FRAMES);
+            let preview = Preview {
+                seek: micros(seek),
+                input: input.to_string(),
+                size,
+                ..Default::default()
+            };
+
+            let (result, took) = smol::block_on(timed(preview.decode_preview_image(|_| {})));
+            match result {
+                Ok(_) => {
+                    total += took;
+                    decoded += 1;
                 }
-                self.end_preview_task_handle = Some(handle);
+                Err(e) => eprintln!("failed to decode the preview at {seek:.1}s: {e}"),
+            }
+        }
 
-                task
-            },
-        ])
+        if decoded == 0 {
+            println!("{size}: nothing decoded");
+            continue;
+        }
+        let average = total / decoded;
+        println!(
+            "{size}: {:.1} ms per preview, {:.1} previews per second",
+            average.as_secs_f64() * 1000.0,
+            1.0 / average.as_secs_f64()
+        );
     }
+
+    Ok(())
 }
 
 fn main() -> Result<(), iced::Error> {
+    // a hidden mode for measuring preview decoding without the GUI
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("--benchmark") => {
+            let Some(input) = args.next() else {
+                eprintln!("usage: media-tweak --benchmark <file>");
+                std::process::exit(2);
+            };
+            if let Err(e) = benchmark(&input) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        // the job itself is loaded by State::new, once the window is up
+        Some("--spec") if args.next().is_none() => {
+            eprintln!("usage: media-tweak --spec <job.json>");
+            std::process::exit(2);
+        }
+        _ => (),
+    }
+
     iced::application(State::new, State::update, State::view)
         .subscription(State::subscription)
         .theme(Theme::custom(
@@ -568,3 +4627,382 @@ fn main() -> Result<(), iced::Error> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a ten second input with the range typed in seconds, then clamped
+    fn clamped(start: f64, end: f64) -> State {
+        let mut state = State {
+            input_length: micros(10.0),
+            end: micros(end),
+            ..Default::default()
+        };
+        state.media.start = micros(start);
+        state.clamp_numbers();
+        state
+    }
+
+    #[test]
+    fn clamp_resolves_negative_ends_from_the_end() {
+        let state = clamped(1.0, -2.0);
+        assert_eq!(state.end, micros(8.0));
+        assert_eq!(state.media.dur, micros(7.0));
+    }
+
+    #[test]
+    fn a_typed_negative_end_is_resolved_before_running() {
+        let mut state = State {
+            input_length: micros(10.0),
+            end: micros(10.0),
+            ..Default::default()
+        };
+        let _ = state.update(Message::StartChange(micros(1.0)));
+        let _ = state.update(Message::EndChange(micros(-5.0)));
+
+        // nothing was submitted, so only the span resolves the end
+        assert!(state.media.dur < 0);
+        assert_eq!(state.clamped_span(), (micros(1.0), micros(4.0)));
+    }
+
+    #[test]
+    fn clamp_keeps_the_range_inside_the_input() {
+        let state = clamped(-1.0, 20.0);
+        assert_eq!(state.media.start, 0);
+        assert_eq!(state.end, micros(10.0));
+
+        let state = clamped(6.0, 4.0);
+        assert_eq!(state.media.start, micros(4.0));
+        assert_eq!(state.media.dur, 0);
+    }
+
+    #[test]
+    fn selected_duration_is_formatted_like_a_clock() {
+        assert_eq!(format_duration(42.5), "0:42.5");
+        assert_eq!(format_duration(3723.54), "1:02:03.5");
+    }
+
+    #[test]
+    fn pasted_ranges_take_subtitle_and_short_times() {
+        assert_eq!(
+            parse_range("00:01:13,500 --> 00:02:01,000"),
+            Ok((73.5, 121.0))
+        );
+        assert_eq!(parse_range("1:13.5 - 2:01"), Ok((73.5, 121.0)));
+        assert_eq!(parse_range("5\u{2013}10"), Ok((5.0, 10.0)));
+        assert!(parse_range("1:30.5:00 --> 2:00").is_err());
+        assert!(parse_range("2:00 --> 1:00").is_err());
+        assert!(parse_range("1:00").is_err());
+    }
+
+    #[test]
+    fn percentages_round_to_frames() {
+        assert_eq!(secs_from_percent(10.0, 95.0, None), 9.5);
+        assert_eq!(secs_from_percent(10.0, 95.0, Some(4.0)), 9.5);
+        // 33.33 seconds falls between frames at 10 fps
+        assert_eq!(secs_from_percent(33.33, 100.0, Some(10.0)), 33.3);
+    }
+
+    #[test]
+    fn end_preview_of_a_short_input_stays_inside_it() {
+        let state = State {
+            input_length: micros(0.3),
+            end: micros(0.3),
+            ..Default::default()
+        };
+        let seek = state.end_preview_seek();
+        assert!((1..micros(0.3)).contains(&seek), "{seek}");
+
+        // a long input still seeks back the whole nudge
+        let state = clamped(0.0, 10.0);
+        assert_eq!(state.end_preview_seek(), micros(9.5));
+    }
+
+    #[test]
+    fn moving_a_boundary_stops_short_of_the_other() {
+        let mut state = clamped(2.0, 5.0);
+        let _ = state.update(Message::EagerStartChange(micros(8.0)));
+        assert_eq!(
+            (state.media.start, state.end),
+            (micros(5.0) - MIN_GAP, micros(5.0))
+        );
+
+        let _ = state.update(Message::EagerEndChange(micros(1.0)));
+        assert_eq!(
+            (state.media.start, state.end),
+            (micros(4.0), micros(4.0) + MIN_GAP)
+        );
+        assert_eq!(state.media.dur, MIN_GAP);
+
+        // an input shorter than the gap keeps the whole of it
+        let mut state = State {
+            input_length: micros(0.5),
+            end: micros(0.5),
+            ..Default::default()
+        };
+        let _ = state.update(Message::EagerStartChange(micros(0.4)));
+        let _ = state.update(Message::EagerEndChange(micros(0.1)));
+        assert_eq!((state.media.start, state.end), (0, micros(0.5)));
+    }
+
+    #[test]
+    fn job_summaries_name_the_range_streams_and_codec() {
+        let media = Media {
+            start: micros(10.0),
+            dur: micros(5.0),
+            use_video: true,
+            use_audio: true,
+            video_codec: VideoCodec::Libx264,
+            crf: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(
+            describe_job(&media),
+            "00:00:10.000 to 00:00:15.000, video, audio, H.264 (software) at crf 20"
+        );
+    }
+
+    #[test]
+    fn queue_progress_weighs_jobs_by_their_duration() {
+        let job = |dur, state| QueuedJob {
+            media: Media {
+                dur,
+                ..Default::default()
+            },
+            state,
+        };
+        let mut state = State {
+            queue: Queue {
+                jobs: vec![
+                    job(micros(10.0), JobState::Running),
+                    job(micros(30.0), JobState::Pending),
+                    // a failed job won't run again, so it isn't waited for
+                    job(micros(100.0), JobState::Failed(String::new())),
+                ],
+            },
+            queue_done: 20.0,
+            ..Default::default()
+        };
+        let _ = state.update(Message::QueueProgress(Progress {
+            out_time: 5.0,
+            speed: Some(2.0),
+        }));
+
+        assert_eq!(state.queue_secs(), (25.0, 60.0));
+        assert_eq!(state.queue_eta, Some(17.5));
+    }
+
+    #[test]
+    fn leaving_a_webm_codec_goes_back_to_the_inputs_container() {
+        let mut state = State {
+            media: Media {
+                input: "clip.mp4".to_string(),
+                output: "clip_edited.mp4".to_string(),
+                ..Default::default()
+            },
+            output_is_generated: true,
+            ..Default::default()
+        };
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Vp9));
+        assert_eq!(state.media.output, "clip_edited.webm");
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Libx264));
+        assert_eq!(state.media.output, "clip_edited.mp4");
+    }
+
+    #[test]
+    fn a_gif_only_keeps_the_video() {
+        let mut state = State {
+            media: Media {
+                input: "clip.mp4".to_string(),
+                output: "clip_edited.mp4".to_string(),
+                use_video: true,
+                use_audio: true,
+                use_subs: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Gif));
+        assert_eq!(state.media.output, "clip_edited.gif");
+        assert!(state.media.use_video && !state.media.use_audio && !state.media.use_subs);
+
+        // picking another container leaves the gif encoder behind for one it can hold
+        let _ = state.update(Message::FormatChange(Container::Mkv));
+        assert_eq!(state.media.output, "clip_edited.mkv");
+        assert_ne!(state.media.video_codec, VideoCodec::Gif);
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Libx264));
+        assert_eq!(state.media.output, "clip_edited.mkv");
+    }
+
+    #[test]
+    fn the_palette_only_offers_what_can_run() {
+        let mut state = State::default();
+        let offered =
+            |state: &State, name| state.actions().iter().any(|(action, _)| *action == name);
+        assert!(!offered(&state, "detect crop"));
+        assert!(!offered(&state, "toggle frame numbers"));
+
+        // dispatched anyway, like a hotkey would
+        let _ = state.update(Message::DetectCrop);
+        assert!(!state.detecting_crop);
+
+        state.input_exists = true;
+        state.media.use_video = true;
+        state.input_info.fps = Some(25.0);
+        assert!(offered(&state, "detect crop"));
+        assert!(offered(&state, "toggle frame numbers"));
+    }
+
+    #[test]
+    fn scrubbing_decodes_one_preview_at_a_time() {
+        let mut state = State::default();
+        state.media.use_video = true;
+
+        let _ = state.update(Message::ScrubChange(1.0));
+        assert!(state.scrub_decoding && !state.scrub_pending);
+
+        // moving on while decoding only remembers to decode the latest position next
+        let _ = state.update(Message::ScrubChange(2.0));
+        let _ = state.update(Message::ScrubChange(3.0));
+        assert!(state.scrub_pending);
+        assert_eq!(state.scrub_position, 3.0);
+
+        let _ = state.update(Message::LoadedScrubPreview(
+            String::new(),
+            Err(PreviewError::SameHash),
+        ));
+        assert!(state.scrub_decoding && !state.scrub_pending);
+
+        // turning scrubbing on while a decode is running waits for it too
+        let _ = state.update(Message::ToggleScrub);
+        assert!(state.scrub_pending);
+    }
+
+    #[test]
+    fn scrub_previews_of_another_input_are_dropped() {
+        let mut state = State::default();
+        state.media.use_video = true;
+        let _ = state.update(Message::ScrubChange(1.0));
+
+        state.media.input = "other.mkv".to_string();
+        let _ = state.probe_input();
+        assert!(!state.scrub_decoding && !state.scrub_pending);
+        let _ = state.update(Message::ScrubChange(2.0));
+
+        // the first input's decode finishing doesn't end the new one's
+        let _ = state.update(Message::LoadedScrubPreview(
+            String::new(),
+            Err(PreviewError::SameHash),
+        ));
+        assert!(state.scrub_decoding);
+    }
+
+    #[test]
+    fn the_crf_is_kept_within_the_encoders_range() {
+        let mut state = State::default();
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Vp9));
+        let _ = state.update(Message::ToggleCrf);
+        let _ = state.update(Message::CrfChange(60));
+        assert_eq!(state.media.crf, Some(60));
+
+        let _ = state.update(Message::VideoCodecChange(VideoCodec::Libx264));
+        assert_eq!(state.media.crf, Some(51));
+    }
+
+    #[test]
+    fn an_invalid_scroll_step_keeps_the_last_one() {
+        let mut state = State::default();
+
+        let _ = state.update(Message::ScrollStepChange("2".to_string()));
+        for str in ["", "-", "abc", "-1", "0", "NaN", "inf"] {
+            let _ = state.update(Message::ScrollStepChange(str.to_string()));
+            assert_eq!(state.settings.scroll_step, 2.0, "{str:?}");
+        }
+        assert_eq!(state.scroll_step_field, "inf");
+    }
+
+    #[test]
+    fn an_invalid_silence_duration_keeps_the_last_one() {
+        let mut state = State::default();
+
+        let _ = state.update(Message::SilenceDurationChange("1.5".to_string()));
+        let _ = state.update(Message::SilenceDurationChange("1.5s".to_string()));
+        assert_eq!(state.silence.min_duration, 1.5);
+        assert_eq!(state.silence_duration_field, "1.5s");
+
+        let _ = state.update(Message::SilenceDurationChange("-1".to_string()));
+        assert_eq!(state.silence.min_duration, 1.5);
+    }
+
+    #[test]
+    fn frame_times_name_the_frame_when_the_rate_is_known() {
+        let time = Some(FrameTime {
+            pts: 1001,
+            secs: 1.001,
+        });
+        let mut state = State::default();
+        assert_eq!(state.describe_frame_time(time), "pts 1001 (1.001s)");
+
+        state.input_info.fps = Some(30000.0 / 1001.0);
+        assert_eq!(
+            state.describe_frame_time(time),
+            "pts 1001 (frame 30, 1.001s)"
+        );
+        assert_eq!(state.describe_frame_time(None), "-");
+    }
+
+    #[test]
+    fn clamp_replaces_nan() {
+        // NaN seconds become 0 microseconds, and infinities stop far past the end
+        let range = |start, end| {
+            let state = clamped(start, end);
+            (state.media.start, state.end)
+        };
+        let end = micros(10.0);
+
+        assert_eq!(range(f64::NAN, f64::NAN), (0, 0));
+        assert_eq!(range(f64::NAN, f64::INFINITY), (0, end));
+        assert_eq!(range(f64::INFINITY, f64::INFINITY), (end, end));
+        assert_eq!(range(f64::NEG_INFINITY, f64::NAN), (0, 0));
+        // -inf counts back past the start of the input
+        assert_eq!(range(1.0, f64::NEG_INFINITY), (0, 0));
+        assert_eq!(range(f64::INFINITY, 4.0), (micros(4.0), micros(4.0)));
+
+        // negative starts are cut off, negative ends count back from the end
+        assert_eq!(range(-3.0, -1.0), (0, micros(9.0)));
+        assert_eq!(range(-3.0, -11.0), (0, 0));
+    }
+
+    #[test]
+    fn nan_times_never_reach_the_args() {
+        let mut state = clamped(0.0, 10.0);
+        state.media.input = "in.mkv".to_string();
+        state.media.output = "out.mkv".to_string();
+        let is_number = |arg: &str| arg.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+        for frame_mode in [false, true] {
+            // a frame rate of 0 turns any frame number into NaN or inf seconds
+            state.frame_mode = frame_mode;
+            state.input_info.fps = Some(0.0);
+
+            for field in ["NaN", "inf", "-inf", "0", "5", "1e400"] {
+                let _ = state.update(Message::StartChange(state.time_from_field(field)));
+                let _ = state.update(Message::EndChange(state.time_from_field(field)));
+                state.clamp_numbers();
+
+                let args = state.media.build_args();
+                for flag in ["-ss", "-t"] {
+                    let value = args
+                        .iter()
+                        .position(|arg| arg == flag)
+                        .map(|i| &args[i + 1]);
+                    assert!(
+                        value.is_some_and(|value| is_number(value)),
+                        "{field} gave {flag} {value:?}"
+                    );
+                }
+            }
+        }
+    }
+}
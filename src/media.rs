@@ -1,18 +1,196 @@
 use std::{
+    collections::VecDeque,
+    ffi::OsStr,
     fmt::{self, Display},
     hash::{DefaultHasher, Hash, Hasher},
+    io, iter,
+    path::{Path, PathBuf},
+    process::{Output, Stdio},
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
 use iced::widget;
-use smol::process::Command;
+use smol::{
+    io::{AsyncBufReadExt, BufReader},
+    process::Command,
+    stream::StreamExt,
+};
 
 use ffmpeg_next as ffmpeg;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs::{TempFile, missing_dir, part_path, replacement_path, versioned_path},
+    log::log,
+};
+
+/// the furthest a time can be from 0 in seconds, about 30 thousand years,
+/// which keeps sums of a few times far from overflowing
+const MAX_SECS: f64 = 1e12;
+
+/// converts seconds to the integer microseconds (AV_TIME_BASE units) that times are kept in.
+/// NaN becomes 0 and anything past `MAX_SECS` stops there
+pub fn micros(secs: f64) -> i64 {
+    (secs.clamp(-MAX_SECS, MAX_SECS) * f64::from(ffmpeg::ffi::AV_TIME_BASE)).round() as i64
+}
+
+/// converts microseconds back to seconds, for showing them
+pub fn secs(micros: i64) -> f64 {
+    micros as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE)
+}
+
+/// microseconds as the seconds ffmpeg's time options take, written from the integer so no digit is lost
+pub fn time_arg(micros: i64) -> String {
+    let sign = if micros < 0 { "-" } else { "" };
+    let micros = micros.unsigned_abs();
+    let (whole, fraction) = (micros / 1_000_000, micros % 1_000_000);
+    if fraction == 0 {
+        format!("{sign}{whole}")
+    } else {
+        let fraction = format!("{fraction:06}");
+        format!("{sign}{whole}.{}", fraction.trim_end_matches('0'))
+    }
+}
+
+/// rescales `ts` between time bases in integers, rounding to the nearest like av_rescale_q
+fn rescale(ts: i64, from: ffmpeg::Rational, to: ffmpeg::Rational) -> i64 {
+    let num = i128::from(ts) * i128::from(from.numerator()) * i128::from(to.denominator());
+    let den = i128::from(from.denominator()) * i128::from(to.numerator());
+    if den == 0 {
+        return 0;
+    }
+    // rounds half away from zero, whatever the signs
+    let (num, den) = if den < 0 { (-num, -den) } else { (num, den) };
+    let rounded = if num < 0 {
+        (num - den / 2) / den
+    } else {
+        (num + den / 2) / den
+    };
+    rounded.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
+
+/// the microseconds that time base ticks stand for
+fn ticks_to_micros(ts: i64, time_base: ffmpeg::Rational) -> i64 {
+    rescale(
+        ts,
+        time_base,
+        ffmpeg::Rational::new(1, ffmpeg::ffi::AV_TIME_BASE),
+    )
+}
+
+/// the time base ticks nearest to microseconds
+fn micros_to_ticks(micros: i64, time_base: ffmpeg::Rational) -> i64 {
+    rescale(
+        micros,
+        ffmpeg::Rational::new(1, ffmpeg::ffi::AV_TIME_BASE),
+        time_base,
+    )
+}
+
+/// (de)serializes microseconds as seconds, which is what job specs are written in
+mod seconds {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(micros: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(super::secs(*micros))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+        let secs = f64::deserialize(deserializer)?;
+        if !secs.is_finite() {
+            return Err(serde::de::Error::custom(format!("invalid time: {secs}")));
+        }
+        Ok(super::micros(secs))
+    }
+}
 
 #[derive(Debug, Default, PartialEq, Clone)]
 pub struct Preview {
+    /// in microseconds, see [`micros`]
     pub seek: i64,
     pub input: String,
     pub prev_hash: u64,
+
+    /// decode up to the requested time instead of using the first frame after the seek
+    pub exact: bool,
+    /// with `exact`, show the last frame before the requested time instead of the one at it,
+    /// which is the last frame a cut ending there keeps
+    pub before: bool,
+
+    pub size: PreviewSize,
+}
+
+/// the largest dimension previews are scaled down to
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum PreviewSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Full,
+}
+
+impl PreviewSize {
+    pub const ALL: [PreviewSize; 4] = [
+        PreviewSize::Small,
+        PreviewSize::Medium,
+        PreviewSize::Large,
+        PreviewSize::Full,
+    ];
+
+    /// the cap in pixels, or None to decode at the source resolution
+    pub fn max_dim(&self) -> Option<u32> {
+        match self {
+            PreviewSize::Small => Some(320),
+            PreviewSize::Medium => Some(640),
+            PreviewSize::Large => Some(1280),
+            PreviewSize::Full => None,
+        }
+    }
+
+    /// fits the dimensions inside the cap, keeping the aspect ratio and never upscaling
+    fn fit(&self, width: u32, height: u32) -> (u32, u32) {
+        match self.max_dim() {
+            Some(max) if width.max(height) > max => {
+                let scale = f64::from(max) / f64::from(width.max(height));
+                (
+                    ((f64::from(width) * scale).round() as u32).max(1),
+                    ((f64::from(height) * scale).round() as u32).max(1),
+                )
+            }
+            _ => (width, height),
+        }
+    }
+}
+
+impl Display for PreviewSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max_dim() {
+            Some(max) => write!(f, "{max}px"),
+            None => write!(f, "full"),
+        }
+    }
+}
+
+/// the image, its histogram, the hash that identifies it and when the frame shows
+pub type PreviewResult =
+    Result<(widget::image::Handle, Histogram, u64, Option<FrameTime>), PreviewError>;
+
+/// the timestamp of a decoded frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTime {
+    /// in the stream's time base
+    pub pts: i64,
+    /// from the start of the input, like cutting times
+    pub secs: f64,
+}
+
+impl FrameTime {
+    /// the frame's number counting from 0, at a constant frame rate
+    pub fn frame(&self, fps: f64) -> i64 {
+        (self.secs * fps).round() as i64
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -20,6 +198,8 @@ pub enum PreviewError {
     Raw(ffmpeg::Error),
     SameHash,
     NoPackets,
+    /// no frame came out of this many packets before the timeout
+    DecodeTimeout(usize),
 }
 
 impl Display for PreviewError {
@@ -28,6 +208,10 @@ impl Display for PreviewError {
             PreviewError::Raw(e) => e.fmt(f),
             PreviewError::SameHash => write!(f, "same encoded hash"),
             PreviewError::NoPackets => write!(f, "no valid packets in input"),
+            PreviewError::DecodeTimeout(packets) => write!(
+                f,
+                "gave up on the preview after {packets} packets without a frame"
+            ),
         }
     }
 }
@@ -35,40 +219,114 @@ impl Display for PreviewError {
 impl std::error::Error for PreviewError {}
 
 impl Preview {
-    pub async fn decode_preview_image(self) -> Result<(widget::image::Handle, u64), PreviewError> {
-        let mut ictx = ffmpeg::format::input(&self.input).map_err(PreviewError::Raw)?;
+    /// how long decoding can go on without producing a frame, for pathological inputs
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    /// how many packets are scanned between calls to `on_progress`
+    const PROGRESS_PACKETS: usize = 100;
+
+    /// decodes the first frame at or after the seek,
+    /// calling `on_progress` with the number of packets scanned when that takes a while
+    pub async fn decode_preview_image(self, mut on_progress: impl FnMut(usize)) -> PreviewResult {
+        let options = input_options(reconnect_args(&self.input).iter().copied());
+        let mut ictx = ffmpeg::format::input_with_dictionary(&self.input, options)
+            .map_err(PreviewError::Raw)?;
 
-        let input = ictx
+        let best = ictx
             .streams()
             .best(ffmpeg_next::media::Type::Video)
             .ok_or(ffmpeg::Error::StreamNotFound)
-            .map_err(PreviewError::Raw)?;
+            .map_err(PreviewError::Raw)?
+            .index();
 
-        let context_decoder = ffmpeg::codec::context::Context::from_parameters(input.parameters())
-            .map_err(PreviewError::Raw)?;
+        // the best stream might use a codec this build can't decode, so fall back to the others
+        let mut candidates = vec![best];
+        candidates.extend(
+            ictx.streams()
+                .filter(|stream| {
+                    stream.index() != best
+                        && stream.parameters().medium() == ffmpeg::media::Type::Video
+                })
+                .map(|stream| stream.index()),
+        );
 
-        let mut decoder = context_decoder
-            .decoder()
-            .video()
-            .map_err(PreviewError::Raw)?;
+        let mut opened = Err(PreviewError::Raw(ffmpeg::Error::DecoderNotFound));
+        for index in candidates {
+            let Some(stream) = ictx.stream(index) else {
+                continue;
+            };
+
+            match open_video_decoder(&stream) {
+                Ok(decoder) => {
+                    if index != best {
+                        log!(
+                            "previewing video stream {index}, since stream {best} can't be decoded"
+                        );
+                    }
+                    opened = Ok((stream, decoder));
+                    break;
+                }
+                Err(e) => {
+                    log!("failed to open a decoder for video stream {index}: {e}");
+                    opened = Err(PreviewError::Raw(e));
+                }
+            }
+        }
+        let (input, mut decoder) = opened?;
+
+        let has_alpha = decoder.format().descriptor().is_some_and(has_alpha);
+
+        let rotation = stream_rotation(&input);
+        if rotation != 0 {
+            log!("rotating preview by {rotation} degrees to match playback");
+        }
 
+        // anamorphic video has non-square pixels, which the image shows stretched like a player would
+        let display_width = square_pixel_width(decoder.width(), decoder.aspect_ratio());
+        if display_width != decoder.width() {
+            log!(
+                "stretching preview from {} to {display_width} pixels wide for its pixel aspect ratio",
+                decoder.width()
+            );
+        }
+        let (width, height) = self.size.fit(display_width, decoder.height());
         let mut scalar = ffmpeg::software::scaling::Context::get(
             decoder.format(),
             decoder.width(),
             decoder.height(),
-            ffmpeg::format::Pixel::RGB24,
-            decoder.width(),
-            decoder.height(),
+            ffmpeg::format::Pixel::RGBA,
+            width,
+            height,
             ffmpeg::software::scaling::Flags::BILINEAR,
         )
         .map_err(PreviewError::Raw)?;
 
         let target_stream = input.index();
+        // the seek is in AV_TIME_BASE units, frame timestamps are in the stream's time base.
+        // rescaling stays in integers so multi-hour seeks don't drift
+        let target_ts = micros_to_ticks(self.seek, input.time_base());
+        let time_base = f64::from(input.time_base());
+        let stream_start = match input.start_time() {
+            ffmpeg::ffi::AV_NOPTS_VALUE => 0,
+            start => start,
+        };
+
         let mut decoded = ffmpeg::util::frame::video::Video::empty();
         let mut rgb_frame = ffmpeg::util::frame::video::Video::empty();
+        // the latest frame before the requested time, for `before`
+        let mut previous = ffmpeg::util::frame::video::Video::empty();
+        let mut has_previous = false;
 
-        ictx.seek(self.seek, i64::MIN..i64::MAX)
-            .map_err(PreviewError::Raw)?;
+        // a keyframe right at the requested time has no frames before it to show,
+        // so only the ones strictly before are seeked to
+        if self.exact && self.before {
+            ictx.seek(self.seek - 1, i64::MIN..self.seek - 1)
+        } else {
+            ictx.seek(self.seek, i64::MIN..i64::MAX)
+        }
+        .map_err(PreviewError::Raw)?;
+
+        let started = Instant::now();
+        let mut scanned = 0;
 
         for packet in ictx.packets().filter_map(|(stream, packet)| {
             if stream.index() == target_stream {
@@ -77,143 +335,3950 @@ impl Preview {
                 None
             }
         }) {
+            scanned += 1;
+            if scanned % Self::PROGRESS_PACKETS == 0 {
+                if started.elapsed() > Self::TIMEOUT {
+                    return Err(PreviewError::DecodeTimeout(scanned));
+                }
+                on_progress(scanned);
+            }
+
             // skip empty packets
             if unsafe { packet.is_empty() } {
                 continue;
             }
 
             let mut hasher = DefaultHasher::new();
-            packet.data().hash(&mut hasher);
-            let new_hash = hasher.finish();
 
-            // make sure that the hash is different before decoding
-            if new_hash == self.prev_hash {
-                return Err(PreviewError::SameHash);
+            // the first packet after the seek is usually the same keyframe for nearby seeks,
+            // so exact previews are identified by the frame that is actually shown instead
+            if !self.exact {
+                packet.data().hash(&mut hasher);
+                let new_hash = hasher.finish();
+
+                // make sure that the hash is different before decoding
+                if new_hash == self.prev_hash {
+                    return Err(PreviewError::SameHash);
+                }
             }
 
             decoder.send_packet(&packet).map_err(PreviewError::Raw)?;
 
-            match decoder.receive_frame(&mut decoded) {
-                // skip the rest of the loop on benign "Resource temporarily unavailable" error
-                Err(ffmpeg::Error::Other { errno: 11 }) => continue,
-                Err(e) => return Err(PreviewError::Raw(e)),
-                _ => {}
+            // skip the rest of the loop until the decoder has a frame
+            if !received(decoder.receive_frame(&mut decoded)).map_err(PreviewError::Raw)? {
+                continue;
+            }
+
+            if self.exact {
+                // discard frames between the keyframe and the requested time,
+                // keeping the latest in case it's the one to show
+                if decoded.timestamp().is_some_and(|ts| ts < target_ts) {
+                    if self.before {
+                        std::mem::swap(&mut decoded, &mut previous);
+                        has_previous = true;
+                    }
+                    continue;
+                }
+                if self.before && has_previous {
+                    std::mem::swap(&mut decoded, &mut previous);
+                }
+
+                self.input.hash(&mut hasher);
+                decoded.timestamp().hash(&mut hasher);
+                if hasher.finish() == self.prev_hash {
+                    return Err(PreviewError::SameHash);
+                }
             }
+            let new_hash = hasher.finish();
+
+            let frame_time = decoded.pts().or(decoded.timestamp()).map(|pts| FrameTime {
+                pts,
+                secs: (pts - stream_start) as f64 * time_base,
+            });
 
             scalar
                 .run(&decoded, &mut rgb_frame)
                 .map_err(PreviewError::Raw)?;
 
-            let mut buf = Vec::new();
-            for (i, rgb) in rgb_frame.data(0).iter().enumerate() {
-                buf.push(*rgb);
-                if (i + 1) % 3 == 0 {
-                    buf.push(u8::MAX);
-                }
-            }
+            // rows can be padded past the width for alignment, which the image can't have
+            let mut buf = packed_rows(
+                rgb_frame.data(0),
+                rgb_frame.stride(0),
+                rgb_frame.width() as usize * 4,
+                rgb_frame.height() as usize,
+            );
 
-            let handle =
-                widget::image::Handle::from_rgba(rgb_frame.width(), rgb_frame.height(), buf);
+            let histogram = Histogram::from_rgba(&buf);
+            if has_alpha {
+                checkerboard(rgb_frame.width(), &mut buf);
+            }
+            let (width, height, buf) =
+                rotate_rgba(rgb_frame.width(), rgb_frame.height(), buf, rotation);
+            let handle = widget::image::Handle::from_rgba(width, height, buf);
 
-            return Ok((handle, new_hash));
+            return Ok((handle, histogram, new_hash, frame_time));
         }
 
         Err(PreviewError::NoPackets)
     }
 }
 
+/// whether a pixel format has an alpha channel, going by its components,
+/// since that's the fourth one or the second one in gray formats
+fn has_alpha(descriptor: ffmpeg::format::pixel::Descriptor) -> bool {
+    matches!(descriptor.nb_components(), 2 | 4)
+}
+
+/// the width a frame shows at with square pixels, given its sample aspect ratio,
+/// which is 0/1 when unknown
+fn square_pixel_width(width: u32, sample_aspect_ratio: ffmpeg::Rational) -> u32 {
+    let (num, den) = (
+        sample_aspect_ratio.numerator(),
+        sample_aspect_ratio.denominator(),
+    );
+    if num <= 0 || den <= 0 || num == den {
+        return width;
+    }
+
+    ((f64::from(width) * f64::from(num) / f64::from(den)).round() as u32).max(1)
+}
+
+/// copies the rows of an image out of a buffer where each starts `stride` bytes after the last
+fn packed_rows(data: &[u8], stride: usize, row_len: usize, height: usize) -> Vec<u8> {
+    if stride == row_len {
+        return data[..row_len * height].to_vec();
+    }
+
+    data.chunks(stride)
+        .take(height)
+        .flat_map(|row| &row[..row_len])
+        .copied()
+        .collect()
+}
+
+/// blends an RGBA image over a checkerboard, so transparent parts show up as such in the preview
+fn checkerboard(width: u32, buf: &mut [u8]) {
+    const SQUARE: usize = 8;
+
+    for (i, pixel) in buf.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i % width as usize, i / width as usize);
+        let background: u16 = if (x / SQUARE + y / SQUARE).is_multiple_of(2) {
+            204
+        } else {
+            153
+        };
+
+        let alpha = u16::from(pixel[3]);
+        for channel in &mut pixel[..3] {
+            *channel = ((u16::from(*channel) * alpha + background * (255 - alpha)) / 255) as u8;
+        }
+        pixel[3] = u8::MAX;
+    }
+}
+
+/// per channel pixel counts of a preview image, in coarse bins
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// red, green and blue
+    pub bins: [[u32; Histogram::BINS]; 3],
+    /// the fraction of pixels with at least one channel at full brightness
+    pub clipped: f64,
+}
+
+impl Histogram {
+    pub const BINS: usize = 32;
+
+    fn from_rgba(buf: &[u8]) -> Self {
+        let mut bins = [[0; Histogram::BINS]; 3];
+        let mut clipped = 0;
+
+        let pixels = buf.chunks_exact(4);
+        let count = pixels.len();
+        for pixel in pixels {
+            for (channel, value) in pixel[..3].iter().enumerate() {
+                bins[channel][usize::from(*value) * Histogram::BINS / 256] += 1;
+            }
+            if pixel[..3].contains(&u8::MAX) {
+                clipped += 1;
+            }
+        }
+
+        Histogram {
+            bins,
+            clipped: clipped as f64 / count.max(1) as f64,
+        }
+    }
+
+    /// whether enough of the image is blown out to be worth pointing out
+    pub fn is_overexposed(&self) -> bool {
+        self.clipped > 0.02
+    }
+}
+
+fn open_video_decoder(stream: &ffmpeg::Stream) -> Result<ffmpeg::decoder::Video, ffmpeg::Error> {
+    ffmpeg::codec::context::Context::from_parameters(stream.parameters())?
+        .decoder()
+        .video()
+}
+
+/// clockwise rotation a player would apply, in multiples of 90 degrees
+fn stream_rotation(stream: &ffmpeg::Stream) -> u32 {
+    let from_matrix = stream.side_data().find_map(|side_data| {
+        if side_data.kind() != ffmpeg::codec::packet::side_data::Type::DisplayMatrix {
+            return None;
+        }
+
+        // a 3x3 matrix of 16.16 fixed point values, read the same way as av_display_rotation_get
+        let matrix: Vec<f64> = side_data
+            .data()
+            .chunks_exact(4)
+            .take(9)
+            .map(|b| f64::from(i32::from_ne_bytes([b[0], b[1], b[2], b[3]])) / 65536.0)
+            .collect();
+        if matrix.len() < 9 {
+            return None;
+        }
+
+        let scale_x = matrix[0].hypot(matrix[3]);
+        let scale_y = matrix[1].hypot(matrix[4]);
+        if scale_x == 0.0 || scale_y == 0.0 {
+            return None;
+        }
+
+        Some(
+            (matrix[1] / scale_y)
+                .atan2(matrix[0] / scale_x)
+                .to_degrees(),
+        )
+    });
+
+    // older muxers only write the rotate tag, which is already clockwise
+    let degrees = from_matrix.or_else(|| {
+        stream
+            .metadata()
+            .get("rotate")
+            .and_then(|rotate| rotate.parse().ok())
+    });
+
+    degrees.map_or(0, |degrees: f64| {
+        ((degrees / 90.0).round() as i64 * 90).rem_euclid(360) as u32
+    })
+}
+
+/// rotates a packed RGBA image clockwise, returning the new dimensions with the pixels
+fn rotate_rgba(width: u32, height: u32, buf: Vec<u8>, rotation: u32) -> (u32, u32, Vec<u8>) {
+    if rotation == 0 {
+        return (width, height, buf);
+    }
+
+    let (w, h) = (width as usize, height as usize);
+    let mut rotated = vec![0; buf.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let (nx, ny, nw) = match rotation {
+                90 => (h - 1 - y, x, h),
+                180 => (w - 1 - x, h - 1 - y, w),
+                _ => (y, w - 1 - x, h),
+            };
+            let src = (y * w + x) * 4;
+            let dst = (ny * nw + nx) * 4;
+            rotated[dst..dst + 4].copy_from_slice(&buf[src..src + 4]);
+        }
+    }
+
+    if rotation == 180 {
+        (width, height, rotated)
+    } else {
+        (height, width, rotated)
+    }
+}
+
 #[derive(Debug, Default, PartialEq, Clone)]
-pub struct Media {
-    pub start: f64,
-    pub dur: f64,
+pub struct LoudnessProbe {
+    pub input: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    pub dur: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    /// integrated loudness in LUFS
+    pub integrated: f64,
+    /// true peak in dBTP
+    pub true_peak: f64,
+}
+
+impl LoudnessProbe {
+    /// runs ffmpeg's ebur128 filter over the range without writing any output
+    pub async fn measure(self) -> Result<Loudness, String> {
+        let seek = time_arg(self.start);
+        let dur = time_arg(self.dur);
+
+        #[rustfmt::skip]
+        let args = [
+            "-nostats",
+            "-ss",  &seek,
+            "-t",   &dur,
+            "-i",   &self.input,
+            "-vn", "-sn", "-dn",
+            "-af",  "ebur128=peak=true:framelog=verbose",
+            "-f",   "null", "-",
+        ];
+
+        let output = run_ffmpeg(args)
+            .await
+            .map_err(|e| format!("loudness measurement failed: {e}"))?;
+
+        let mut integrated = None;
+        let mut true_peak = None;
 
+        // the summary is printed last, so later matches win
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            let line = line.trim();
+            let value = |rest: &str| rest.split_whitespace().next()?.parse::<f64>().ok();
+
+            if let Some(rest) = line.strip_prefix("I:") {
+                integrated = value(rest);
+            } else if let Some(rest) = line.strip_prefix("Peak:") {
+                true_peak = value(rest);
+            }
+        }
+
+        match (integrated, true_peak) {
+            (Some(integrated), Some(true_peak)) => Ok(Loudness {
+                integrated,
+                true_peak,
+            }),
+            _ => Err("no loudness summary in ffmpeg output".to_string()),
+        }
+    }
+}
+
+/// a picture of the audio in a range, shown instead of previews for audio-only inputs
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct WaveformProbe {
     pub input: String,
-    pub output: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    pub dur: i64,
+}
 
-    pub use_video: bool,
-    pub use_audio: bool,
-    pub use_subs: bool,
-    pub use_extra_streams: bool,
+impl WaveformProbe {
+    const WIDTH: u32 = 1000;
+    const HEIGHT: u32 = 150;
+
+    /// has ffmpeg's showwavespic draw the range, read back as raw RGBA since no image codecs are built in
+    pub async fn render(self) -> Result<widget::image::Handle, String> {
+        let seek = time_arg(self.start);
+        let dur = time_arg(self.dur);
+        let filter = format!(
+            "showwavespic=s={}x{}:split_channels=1:colors=0x00ffff",
+            Self::WIDTH,
+            Self::HEIGHT
+        );
+
+        #[rustfmt::skip]
+        let args = [
+            "-nostats",
+            "-ss",      &seek,
+            "-t",       &dur,
+            "-i",       &self.input,
+            "-vn", "-sn", "-dn",
+            "-filter_complex", &filter,
+            "-frames:v", "1",
+            "-f",       "rawvideo",
+            "-pix_fmt", "rgba",
+            "-",
+        ];
+
+        let output = run_ffmpeg(args)
+            .await
+            .map_err(|e| format!("waveform rendering failed: {e}"))?;
+
+        let expected = (Self::WIDTH * Self::HEIGHT * 4) as usize;
+        if output.stdout.len() != expected {
+            return Err(format!(
+                "waveform rendering gave {} bytes instead of {expected}",
+                output.stdout.len()
+            ));
+        }
+
+        Ok(widget::image::Handle::from_rgba(
+            Self::WIDTH,
+            Self::HEIGHT,
+            output.stdout,
+        ))
+    }
 }
 
-impl Media {
-    /// uses the parameters and the input to create the output
-    pub async fn create(self) -> Result<(), String> {
-        let seek = self.start.to_string();
-        let dur = self.dur.to_string();
+/// the audio of a range, played through the system to hear where a cut lands
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AudioPlayback {
+    pub input: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    pub dur: i64,
+}
+
+impl AudioPlayback {
+    /// plays the range with ffplay until it ends. dropping the future stops the playback
+    pub async fn play(self) -> Result<(), String> {
+        let seek = time_arg(self.start);
+        let dur = time_arg(self.dur);
 
         #[rustfmt::skip]
-        let mut args = vec![
+        let args = [
+            "-hide_banner", "-loglevel", "error",
+            "-nodisp", "-autoexit",
             "-ss",  &seek,
             "-t",   &dur,
             "-i",   &self.input,
         ];
 
-        if self.use_audio {
-            args.push("-c:a");
-            args.push("copy");
-        } else {
-            args.push("-an");
+        let status = Command::new("ffplay")
+            .args(args)
+            .kill_on_drop(true)
+            .status()
+            .await
+            .map_err(|e| format!("failed to run ffplay: {e}"))?;
+
+        if !status.success() {
+            return Err(format!("audio playback returned {status}"));
         }
+        Ok(())
+    }
+}
 
-        if self.use_video {
-            args.push("-c:v");
-            args.push("copy");
-        } else {
-            args.push("-vn");
+/// a single image of evenly sampled frames from the whole input, for cataloging
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContactSheet {
+    pub input: String,
+    /// the input's duration in seconds, which the frames are spread over
+    pub length: f64,
+
+    pub rows: u32,
+    pub columns: u32,
+    /// the width of each frame in pixels
+    pub thumb_width: u32,
+}
+
+impl Default for ContactSheet {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            length: 0.0,
+            rows: 4,
+            columns: 4,
+            thumb_width: 320,
         }
+    }
+}
 
-        if self.use_subs {
-            args.push("-c:s");
-            args.push("copy");
-        } else {
-            args.push("-sn");
+impl ContactSheet {
+    /// samples the frames with the fps filter and lays them out with tile
+    pub async fn create(self, output: PathBuf) -> Result<PathBuf, String> {
+        let frames = self.rows * self.columns;
+        if frames == 0 || self.thumb_width == 0 {
+            return Err("the contact sheet needs at least one row, column and pixel".to_string());
+        }
+        if !self.length.is_finite() || self.length <= 0.0 {
+            return Err("the input has no known duration to sample".to_string());
         }
 
-        if self.use_extra_streams {
-            args.push("-map");
-            args.push("0");
+        let filter = format!(
+            "fps={},scale={}:-2,tile={}x{}",
+            f64::from(frames) / self.length,
+            self.thumb_width,
+            self.columns,
+            self.rows
+        );
+
+        #[rustfmt::skip]
+        let args = [
+            "-nostats", "-y",
+            "-i",           &self.input,
+            "-an", "-sn", "-dn",
+            "-vf",          &filter,
+            "-frames:v",    "1",
+        ];
+
+        log!("running ffmpeg {} {}", args.join(" "), output.display());
+
+        run_ffmpeg(args.iter().map(OsStr::new).chain([output.as_os_str()]))
+            .await
+            .map_err(|e| format!("contact sheet creation failed: {e}"))?;
+        Ok(output)
+    }
+}
+
+/// one image for each keyframe in the range, or for every few frames, named by when they show
+#[derive(Debug, PartialEq, Clone)]
+pub struct StillExport {
+    pub input: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    /// to the end of the input without one
+    pub dur: Option<i64>,
+
+    /// every this many frames instead of every keyframe
+    pub every: Option<u32>,
+    /// stops after this many images
+    pub max_count: u32,
+    pub format: StillFormat,
+}
+
+impl Default for StillExport {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            start: 0,
+            dur: None,
+            every: None,
+            max_count: 100,
+            format: StillFormat::default(),
         }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StillFormat {
+    #[default]
+    Jpg,
+    Png,
+    Webp,
+}
 
-        args.push(&self.output);
+impl StillFormat {
+    pub const ALL: [StillFormat; 3] = [StillFormat::Jpg, StillFormat::Png, StillFormat::Webp];
 
-        match Command::new("ffmpeg").args(&args).spawn() {
-            Err(e) => Err(e.to_string()),
-            Ok(mut child) => match child.status().await {
-                Err(e) => Err(e.to_string()),
-                Ok(status) => {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(format!(
-                            "ffmpeg returned {status}. Check stderr for full error"
-                        ))
-                    }
-                }
-            },
+    pub fn extension(&self) -> &'static str {
+        match self {
+            StillFormat::Jpg => "jpg",
+            StillFormat::Png => "png",
+            StillFormat::Webp => "webp",
         }
     }
+}
 
-    /// updates the Media with the input parameters, returning the input length.
-    /// by default, we use all streams that exist
-    pub fn update_video_params(&mut self) -> Result<f64, ffmpeg::Error> {
-        // try to load the media
-        let context = ffmpeg::format::input(&self.input)?;
+impl Display for StillFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+impl StillExport {
+    /// the arguments for writing the images into `dir`, named by their milliseconds into the input
+    pub fn args(&self, dir: &Path) -> Vec<String> {
+        let select = match self.every {
+            Some(every) => format!("select='not(mod(n,{}))'", every.max(1)),
+            None => "select='eq(pict_type,I)'".to_string(),
+        };
+        // the timestamps stay the input's and count in milliseconds, so they can name the files
+        let filter = format!("{select},settb=1/1000");
+        let seek = time_arg(self.start);
+        let dur = self.dur.map(time_arg);
+        let max_count = self.max_count.to_string();
 
-        let mut streams = context.streams();
+        let mut args = vec!["-nostats", "-y", "-copyts", "-ss", &seek];
+        if let Some(dur) = &dur {
+            args.extend(["-t", dur]);
+        }
 
-        self.use_video =
-            streams.any(|stream| stream.parameters().medium() == ffmpeg::media::Type::Video);
+        #[rustfmt::skip]
+        args.extend([
+            "-i",               &self.input,
+            "-an", "-sn", "-dn",
+            "-vf",              &filter,
+            "-fps_mode",        "vfr",
+            "-enc_time_base:v", "1/1000",
+            "-frame_pts",       "1",
+            "-frames:v",        &max_count,
+        ]);
 
-        self.use_audio =
-            streams.any(|stream| stream.parameters().medium() == ffmpeg::media::Type::Audio);
+        let stem = Path::new(&self.input)
+            .file_stem()
+            .map_or("still".into(), |stem| stem.to_string_lossy());
+        let pattern = dir.join(format!("{stem}_%dms.{}", self.format.extension()));
 
-        self.use_subs =
-            streams.any(|stream| stream.parameters().medium() == ffmpeg::media::Type::Subtitle);
+        let mut args: Vec<String> = args.into_iter().map(String::from).collect();
+        args.push(pattern.to_string_lossy().into_owned());
+        args
+    }
 
-        self.use_extra_streams = context.nb_streams()
-            > self.use_video as u32 + self.use_audio as u32 + self.use_subs as u32;
+    pub async fn export(self, dir: PathBuf) -> Result<PathBuf, String> {
+        if self.max_count == 0 {
+            return Err("the stills need a count of at least one".to_string());
+        }
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("failed to create the folder {}: {e}", dir.display()))?;
+
+        let args = self.args(&dir);
+        log!("running ffmpeg {}", args.join(" "));
+
+        run_ffmpeg(&args)
+            .await
+            .map_err(|e| format!("exporting the stills failed: {e}"))?;
+        Ok(dir)
+    }
+}
+
+/// finds where the input's keyframes are, which is where copying can cut exactly
+#[derive(Debug, Clone)]
+pub struct KeyframeProbe {
+    pub input: String,
+    pub input_args: Vec<String>,
+    /// the video stream to scan, as an absolute index
+    pub stream: usize,
+    /// the boundaries to look for keyframes around, in microseconds, see [`micros`]
+    pub start: i64,
+    pub end: i64,
+}
+
+impl KeyframeProbe {
+    /// how far to either side of a boundary keyframes are looked for, in microseconds
+    pub const WINDOW: i64 = 30_000_000;
+
+    /// the stretches of the input read around the boundaries, as one when they overlap
+    fn windows(&self) -> Vec<(i64, i64)> {
+        let start = (
+            (self.start - Self::WINDOW).max(0),
+            self.start + Self::WINDOW,
+        );
+        let end = ((self.end - Self::WINDOW).max(0), self.end + Self::WINDOW);
+        if end.0 <= start.1 {
+            vec![(start.0, end.1.max(start.1))]
+        } else {
+            vec![start, end]
+        }
+    }
+
+    /// reads the packets around the boundaries, returning the keyframe times in microseconds from its start
+    pub async fn scan(self) -> Result<Vec<i64>, String> {
+        let mut ictx = ffmpeg::format::input_with_dictionary(
+            &self.input,
+            opening_options(&self.input, &self.input_args),
+        )
+        .map_err(|e| e.to_string())?;
+        let stream = ictx
+            .stream(self.stream)
+            .ok_or_else(|| format!("the input has no stream {}", self.stream))?;
+        let time_base = stream.time_base();
+        // cutting times count from the start of the input, not from its first timestamp
+        let start = match stream.start_time() {
+            ffmpeg::ffi::AV_NOPTS_VALUE => 0,
+            start => start,
+        };
+        let offset = ticks_to_micros(start, time_base);
+
+        let mut keyframes = Vec::new();
+        for (low, high) in self.windows() {
+            // seeking lands on the keyframe at or before the window, which is then read up to its end
+            let ts = offset + low;
+            ictx.seek(ts, ..ts).map_err(|e| e.to_string())?;
+
+            for (stream, packet) in ictx.packets() {
+                if stream.index() != self.stream {
+                    continue;
+                }
+                let Some(pts) = packet.pts() else {
+                    continue;
+                };
+                let time = ticks_to_micros(pts - start, time_base);
+                if time > high {
+                    break;
+                }
+                if packet.is_key() && time >= low {
+                    keyframes.push(time);
+                }
+            }
+        }
+        // packets come in decoding order, which can differ from presentation order
+        keyframes.sort_unstable();
+        keyframes.dedup();
+
+        Ok(keyframes)
+    }
+}
+
+/// a small, quickly decoded copy of the input's video, which previews can be decoded from instead
+#[derive(Debug, Clone)]
+pub struct Proxy {
+    pub input: String,
+    /// the video stream to copy, as an absolute index
+    pub stream: usize,
+}
+
+impl Proxy {
+    pub const HEIGHT: u32 = 360;
+
+    /// the input's timestamps are kept, so previews seek to the same frames in both
+    pub async fn create(self, output: PathBuf) -> Result<PathBuf, String> {
+        let map = format!("0:{}", self.stream);
+        let scale = format!("scale=-2:{}", Self::HEIGHT);
+
+        // short GOPs keep the seeking for each preview cheap
+        #[rustfmt::skip]
+        let args = [
+            "-nostats", "-y",
+            "-i",           &self.input,
+            "-map",         &map,
+            "-an", "-sn", "-dn",
+            "-copyts",
+            "-vf",          &scale,
+            "-c:v",         "libx264",
+            "-preset",      "ultrafast",
+            "-crf",         "28",
+            "-g",           "10",
+        ];
+
+        log!("running ffmpeg {} {}", args.join(" "), output.display());
+
+        // the proxy is abandoned whenever the input changes, which drops this
+        run_ffmpeg(args.iter().map(OsStr::new).chain([output.as_os_str()]))
+            .await
+            .map_err(|e| format!("proxy creation failed: {e}"))?;
+        Ok(output)
+    }
+}
+
+/// a file embedded in the input, like cover art or a font the subtitles need
+#[derive(Debug, PartialEq, Clone)]
+pub struct Attachment {
+    /// the absolute stream index
+    pub index: usize,
+    /// the name the muxer stored, if any
+    pub filename: Option<String>,
+    /// the codec name, like "mjpeg" or "ttf"
+    pub codec: String,
+    /// cover art is stored as a single-frame video stream instead of an attachment stream
+    pub cover_art: bool,
+}
+
+impl Attachment {
+    /// the name to suggest when saving, guessing an extension from the codec if there is no filename
+    pub fn default_name(&self) -> String {
+        if let Some(filename) = &self.filename {
+            return filename.clone();
+        }
+
+        let extension = match self.codec.as_str() {
+            "mjpeg" => "jpg",
+            "png" => "png",
+            "ttf" => "ttf",
+            "otf" => "otf",
+            _ => "bin",
+        };
+        let kind = if self.cover_art {
+            "cover"
+        } else {
+            "attachment"
+        };
+        format!("{kind}{}.{extension}", self.index)
+    }
+
+    /// attachment streams can only be dumped while opening the input, cover art is copied like a stream
+    pub async fn extract(self, input: String, output: PathBuf) -> Result<PathBuf, String> {
+        let map = format!("0:{}", self.index);
+        let dump = format!("-dump_attachment:{}", self.index);
+        let mut args = vec![OsStr::new("-nostats"), OsStr::new("-y")];
+
+        if self.cover_art {
+            #[rustfmt::skip]
+            args.extend([
+                "-i",         &input,
+                "-map",       &map,
+                "-c",         "copy",
+                "-frames:v",  "1",
+            ].map(OsStr::new));
+            args.push(output.as_os_str());
+        } else {
+            args.extend([OsStr::new(&dump), output.as_os_str()]);
+            // ffmpeg insists on an output, so give it an empty one
+            args.extend(["-i", &input, "-t", "0", "-f", "null", "-"].map(OsStr::new));
+        }
+
+        log!("extracting stream {} to {}", self.index, output.display());
+
+        let result = run_ffmpeg(args).await;
+
+        // dumping succeeds even when the empty output complains, so check the file instead
+        match result {
+            _ if output.exists() => Ok(output),
+            Err(e) => Err(format!("extracting stream {} failed: {e}", self.index)),
+            Ok(_) => Err(format!(
+                "extracting stream {} didn't write {}",
+                self.index,
+                output.display()
+            )),
+        }
+    }
+}
+
+/// changes to the input's metadata that leave its streams alone. ffmpeg can't edit a file in place,
+/// so everything is copied into a new file without trimming, which is then renamed over the input
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MetadataEdit {
+    pub input: String,
+    /// replaces the title, kept when None
+    pub title: Option<String>,
+    /// the clockwise rotation players should show the first video stream with, kept when None
+    pub rotation: Option<u32>,
+}
+
+impl MetadataEdit {
+    pub const ROTATIONS: [u32; 4] = [0, 90, 180, 270];
+
+    /// the arguments for writing the edited copy to `output`
+    pub fn args(&self, output: &Path) -> Vec<String> {
+        let mut args = vec!["-nostats", "-y"];
+
+        // the display matrix turns counterclockwise
+        let rotation = self
+            .rotation
+            .map(|rotation| (-(rotation as i32)).to_string());
+        if let Some(rotation) = &rotation {
+            args.extend(["-display_rotation:v:0", rotation]);
+        }
+
+        #[rustfmt::skip]
+        args.extend([
+            "-i",               &self.input,
+            "-map",             "0",
+            "-c",               "copy",
+            "-map_metadata",    "0",
+            "-map_chapters",    "0",
+        ]);
+
+        let title = self.title.as_ref().map(|title| format!("title={title}"));
+        if let Some(title) = &title {
+            args.extend(["-metadata", title]);
+        }
+
+        let mut args: Vec<String> = args.into_iter().map(String::from).collect();
+        args.push(output.to_string_lossy().into_owned());
+        args
+    }
+
+    pub async fn apply(self) -> Result<(), String> {
+        let input = Path::new(&self.input);
+        // removed again if anything fails before it replaces the input
+        let temp = TempFile(replacement_path(input));
+        let args = self.args(&temp.0);
+        log!("running ffmpeg {}", args.join(" "));
+
+        run_ffmpeg(&args)
+            .await
+            .map_err(|e| format!("rewriting the metadata failed: {e}"))?;
+
+        std::fs::rename(&temp.0, input)
+            .map_err(|e| format!("failed to replace {}: {e}", input.display()))
+    }
+}
+
+/// a crop rectangle in pixels, written like ffmpeg's `w:h:x:y`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Crop {
+    pub width: u32,
+    pub height: u32,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl FromStr for Crop {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<u32> = s
+            .split(':')
+            .map(|value| value.trim().parse())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("invalid crop '{s}': {e}"))?;
+
+        match values[..] {
+            [width, height, x, y] if width > 0 && height > 0 => Ok(Crop {
+                width,
+                height,
+                x,
+                y,
+            }),
+            _ => Err(format!("invalid crop '{s}', expected w:h:x:y")),
+        }
+    }
+}
+
+impl Display for Crop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}:{}", self.width, self.height, self.x, self.y)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CropProbe {
+    pub input: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    pub dur: i64,
+}
+
+impl CropProbe {
+    /// the longest stretch of the range that gets scanned from its middle, in microseconds
+    const SAMPLE: i64 = 20_000_000;
+
+    /// runs ffmpeg's cropdetect filter over a sample of the range and returns its final suggestion
+    pub async fn detect(self) -> Result<Crop, String> {
+        let sample = self.dur.min(Self::SAMPLE);
+        let seek = time_arg(self.start + (self.dur - sample) / 2);
+        let sample = time_arg(sample);
+
+        #[rustfmt::skip]
+        let args = [
+            "-nostats",
+            "-ss",  &seek,
+            "-t",   &sample,
+            "-i",   &self.input,
+            "-an", "-sn", "-dn",
+            "-vf",  "cropdetect",
+            "-f",   "null", "-",
+        ];
+
+        let output = run_ffmpeg(args)
+            .await
+            .map_err(|e| format!("crop detection failed: {e}"))?;
+
+        // cropdetect settles as it sees more frames, so the last suggestion wins
+        String::from_utf8_lossy(&output.stderr)
+            .lines()
+            .rev()
+            .filter_map(|line| line.rsplit_once("crop=")?.1.split_whitespace().next())
+            .find_map(|crop| crop.parse().ok())
+            .ok_or_else(|| "no crop suggestion in ffmpeg output".to_string())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SilenceProbe {
+    pub input: String,
+    /// in microseconds, see [`micros`]
+    pub start: i64,
+    pub dur: i64,
+
+    /// audio quieter than this many dB counts as silence
+    pub threshold: f64,
+    /// silences shorter than this many seconds are ignored
+    pub min_duration: f64,
+}
+
+impl Default for SilenceProbe {
+    fn default() -> Self {
+        Self {
+            input: String::new(),
+            start: 0,
+            dur: 0,
+            threshold: -50.0,
+            min_duration: 0.5,
+        }
+    }
+}
+
+impl SilenceProbe {
+    /// silences starting or ending this many microseconds from the edges of the range count as touching them
+    const EDGE: i64 = 50_000;
+
+    /// runs ffmpeg's silencedetect filter over the range, returning the start and end in microseconds
+    /// with the silence at either edge trimmed off
+    pub async fn detect(self) -> Result<(i64, i64), String> {
+        let seek = time_arg(self.start);
+        let dur = time_arg(self.dur);
+        let filter = format!(
+            "silencedetect=noise={}dB:d={}",
+            self.threshold, self.min_duration
+        );
+
+        #[rustfmt::skip]
+        let args = [
+            "-nostats",
+            "-ss",  &seek,
+            "-t",   &dur,
+            "-i",   &self.input,
+            "-vn", "-sn", "-dn",
+            "-af",  &filter,
+            "-f",   "null", "-",
+        ];
+
+        let output = run_ffmpeg(args)
+            .await
+            .map_err(|e| format!("silence detection failed: {e}"))?;
+
+        // pairs of silence_start and silence_end, relative to the seek.
+        // a silence running into the end of the range never gets an end
+        let mut silences: Vec<(i64, Option<i64>)> = Vec::new();
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            let value = |key: &str| {
+                line.split_once(key)?
+                    .1
+                    .split_whitespace()
+                    .next()?
+                    .parse::<f64>()
+                    .ok()
+                    .map(micros)
+            };
+
+            if let Some(start) = value("silence_start: ") {
+                silences.push((start, None));
+            } else if let Some(end) = value("silence_end: ")
+                && let Some((_, last_end)) = silences.last_mut()
+            {
+                *last_end = Some(end);
+            }
+        }
+
+        let mut start = 0;
+        let mut end = self.dur;
+        if let Some((silence_start, Some(silence_end))) = silences.first()
+            && *silence_start < Self::EDGE
+        {
+            start = *silence_end;
+        }
+        if let Some((silence_start, silence_end)) = silences.last()
+            && silence_end.is_none_or(|silence_end| silence_end > self.dur - Self::EDGE)
+            && *silence_start >= start
+        {
+            end = *silence_start;
+        }
+
+        if end <= start {
+            return Err("the whole range is silent".to_string());
+        }
+
+        Ok((self.start + start, self.start + end))
+    }
+}
+
+/// how the video stream gets written to the output
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoCodec {
+    #[default]
+    Copy,
+    Libx264,
+    H264Nvenc,
+    HevcQsv,
+    H264Vaapi,
+    Vp9,
+    Av1Aom,
+    Av1Svt,
+    Gif,
+}
+
+impl VideoCodec {
+    pub const ALL: [VideoCodec; 9] = [
+        VideoCodec::Copy,
+        VideoCodec::Libx264,
+        VideoCodec::H264Nvenc,
+        VideoCodec::HevcQsv,
+        VideoCodec::H264Vaapi,
+        VideoCodec::Vp9,
+        VideoCodec::Av1Aom,
+        VideoCodec::Av1Svt,
+        VideoCodec::Gif,
+    ];
+
+    /// the ffmpeg encoder name, or None when copying
+    pub fn encoder(&self) -> Option<&'static str> {
+        match self {
+            VideoCodec::Copy => None,
+            VideoCodec::Libx264 => Some("libx264"),
+            VideoCodec::H264Nvenc => Some("h264_nvenc"),
+            VideoCodec::HevcQsv => Some("hevc_qsv"),
+            VideoCodec::H264Vaapi => Some("h264_vaapi"),
+            VideoCodec::Vp9 => Some("libvpx-vp9"),
+            VideoCodec::Av1Aom => Some("libaom-av1"),
+            VideoCodec::Av1Svt => Some("libsvtav1"),
+            VideoCodec::Gif => Some("gif"),
+        }
+    }
+
+    /// constant quality defaults, since these encoders otherwise pick a low bitrate
+    pub fn quality_args(&self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::Vp9 => &["-crf", "31", "-b:v", "0", "-row-mt", "1"],
+            VideoCodec::Av1Aom => &["-crf", "30", "-b:v", "0", "-cpu-used", "6", "-row-mt", "1"],
+            VideoCodec::Av1Svt => &["-crf", "35", "-preset", "8"],
+            _ => &[],
+        }
+    }
+
+    /// whether the encoder takes a constant rate factor, which the hardware ones don't
+    pub fn supports_crf(&self) -> bool {
+        self.max_crf().is_some()
+    }
+
+    /// the highest constant rate factor the encoder takes, if it takes one
+    pub fn max_crf(&self) -> Option<u8> {
+        match self {
+            VideoCodec::Libx264 => Some(51),
+            VideoCodec::Vp9 | VideoCodec::Av1Aom | VideoCodec::Av1Svt => Some(63),
+            _ => None,
+        }
+    }
+
+    /// whether the output has to be WebM, which only takes opus/vorbis audio and WebVTT subtitles
+    pub fn is_webm(&self) -> bool {
+        matches!(
+            self,
+            VideoCodec::Vp9 | VideoCodec::Av1Aom | VideoCodec::Av1Svt
+        )
+    }
+
+    /// the container the output has to be written in, for the encoders that need their own
+    pub fn container(&self) -> Option<Container> {
+        match self {
+            VideoCodec::Gif => Some(Container::Gif),
+            codec if codec.is_webm() => Some(Container::Webm),
+            _ => None,
+        }
+    }
+
+    /// whether the encoder can write an alpha channel
+    pub fn supports_alpha(&self) -> bool {
+        matches!(self, VideoCodec::Copy | VideoCodec::Vp9)
+    }
+
+    /// AV1 encodes are many times slower than realtime on most machines
+    pub fn is_slow(&self) -> bool {
+        matches!(self, VideoCodec::Av1Aom | VideoCodec::Av1Svt)
+    }
+
+    pub fn is_hardware(&self) -> bool {
+        matches!(
+            self,
+            VideoCodec::H264Nvenc | VideoCodec::HevcQsv | VideoCodec::H264Vaapi
+        )
+    }
+
+    /// args that initialize the encoding device, placed before the input
+    pub fn device_args(&self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::HevcQsv => &["-init_hw_device", "qsv=hw", "-filter_hw_device", "hw"],
+            VideoCodec::H264Vaapi => &["-vaapi_device", "/dev/dri/renderD128"],
+            _ => &[],
+        }
+    }
+
+    /// the filter that uploads decoded frames to the encoding device
+    pub fn upload_filter(&self) -> Option<&'static str> {
+        match self {
+            VideoCodec::HevcQsv => Some("hwupload=extra_hw_frames=64,format=qsv"),
+            VideoCodec::H264Vaapi => Some("format=nv12,hwupload"),
+            _ => None,
+        }
+    }
+
+    /// parses a comma separated list of encoder names, like "h264_nvenc, libx264"
+    pub fn parse_list(str: &str) -> Result<Vec<VideoCodec>, String> {
+        str.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                VideoCodec::ALL
+                    .into_iter()
+                    .find(|codec| codec.encoder() == Some(name))
+                    .ok_or_else(|| format!("unknown encoder: {name}"))
+            })
+            .collect()
+    }
+
+    /// the codecs whose encoders exist in this ffmpeg build
+    pub fn available() -> Vec<VideoCodec> {
+        VideoCodec::ALL
+            .into_iter()
+            .filter(|codec| {
+                codec
+                    .encoder()
+                    .is_none_or(|name| ffmpeg::encoder::find_by_name(name).is_some())
+            })
+            .collect()
+    }
+}
+
+impl Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoCodec::Copy => write!(f, "copy"),
+            VideoCodec::Libx264 => write!(f, "H.264 (software)"),
+            VideoCodec::H264Nvenc => write!(f, "H.264 (NVENC)"),
+            VideoCodec::HevcQsv => write!(f, "HEVC (Quick Sync)"),
+            VideoCodec::H264Vaapi => write!(f, "H.264 (VA-API)"),
+            VideoCodec::Vp9 => write!(f, "VP9 (WebM)"),
+            VideoCodec::Av1Aom => write!(f, "AV1 (WebM, libaom)"),
+            VideoCodec::Av1Svt => write!(f, "AV1 (WebM, SVT)"),
+            VideoCodec::Gif => write!(f, "GIF"),
+        }
+    }
+}
+
+/// how the output gets split into independently playable parts
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Split {
+    #[default]
+    Off,
+    /// a number of equal parts
+    Parts(u32),
+    /// parts of at most this many seconds
+    MaxDuration(f64),
+}
+
+impl Split {
+    /// whether a clip of `dur` microseconds gets split at all
+    pub fn splits(&self, dur: i64) -> bool {
+        self.segment_option(0, dur).is_some()
+    }
+
+    /// the segment muxer's option for splitting a clip of `dur` microseconds starting at `start`,
+    /// if splitting has any effect. equal parts get their cut points,
+    /// since repeating a segment time can leave a sliver of an extra part at the end
+    fn segment_option(&self, start: i64, dur: i64) -> Option<(&'static str, String)> {
+        match *self {
+            Split::Parts(parts) if parts > 1 => {
+                let cuts = (1..parts)
+                    .map(|part| {
+                        let offset = i128::from(dur) * i128::from(part) / i128::from(parts);
+                        time_arg(start + offset as i64)
+                    })
+                    .collect::<Vec<_>>();
+                Some(("-segment_times", cuts.join(",")))
+            }
+            Split::MaxDuration(max) if max > 0.0 && micros(max) < dur => {
+                Some(("-segment_time", time_arg(micros(max))))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// how subtitle streams get written to the output
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleCodec {
+    /// whatever the output container takes
+    #[default]
+    Auto,
+    Copy,
+    MovText,
+    Srt,
+    Ass,
+    WebVtt,
+}
+
+/// subtitle codecs that are pictures, which can't be converted to text
+const BITMAP_SUBTITLES: [&str; 4] = ["hdmv_pgs_subtitle", "dvd_subtitle", "dvb_subtitle", "xsub"];
+
+impl SubtitleCodec {
+    pub const ALL: [SubtitleCodec; 6] = [
+        SubtitleCodec::Auto,
+        SubtitleCodec::Copy,
+        SubtitleCodec::MovText,
+        SubtitleCodec::Srt,
+        SubtitleCodec::Ass,
+        SubtitleCodec::WebVtt,
+    ];
+
+    /// picks the codec for the output's container when set to Auto
+    pub fn resolve(&self, output: &str) -> SubtitleCodec {
+        if *self != SubtitleCodec::Auto {
+            return *self;
+        }
+
+        if is_mp4(output) {
+            SubtitleCodec::MovText
+        } else if container(output).as_deref() == Some("webm") {
+            SubtitleCodec::WebVtt
+        } else {
+            SubtitleCodec::Copy
+        }
+    }
+
+    /// the ffmpeg encoder name, for a resolved codec
+    pub fn encoder(&self) -> &'static str {
+        match self {
+            SubtitleCodec::Auto | SubtitleCodec::Copy => "copy",
+            SubtitleCodec::MovText => "mov_text",
+            SubtitleCodec::Srt => "srt",
+            SubtitleCodec::Ass => "ass",
+            SubtitleCodec::WebVtt => "webvtt",
+        }
+    }
+}
+
+impl Display for SubtitleCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubtitleCodec::Auto => write!(f, "auto"),
+            other => write!(f, "{}", other.encoder()),
+        }
+    }
+}
+
+/// how an MP4/MOV output is laid out for streaming
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mp4Layout {
+    /// whatever the muxer does by default, with the index at the end
+    Plain,
+    /// moves the index to the front so playback can start before the download finishes
+    #[default]
+    Faststart,
+    /// fragments at every keyframe, for live-style streaming
+    Fragmented,
+}
+
+impl Mp4Layout {
+    pub const ALL: [Mp4Layout; 3] = [
+        Mp4Layout::Plain,
+        Mp4Layout::Faststart,
+        Mp4Layout::Fragmented,
+    ];
+
+    pub fn movflags(&self) -> Option<&'static str> {
+        match self {
+            Mp4Layout::Plain => None,
+            Mp4Layout::Faststart => Some("+faststart"),
+            Mp4Layout::Fragmented => Some("+frag_keyframe+empty_moov+default_base_moof"),
+        }
+    }
+}
+
+impl Display for Mp4Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mp4Layout::Plain => write!(f, "plain"),
+            Mp4Layout::Faststart => write!(f, "faststart"),
+            Mp4Layout::Fragmented => write!(f, "fragmented"),
+        }
+    }
+}
+
+/// whether a path is in a container that takes movflags
+pub fn is_mp4(path: &str) -> bool {
+    matches!(container(path).as_deref(), Some("mp4" | "m4v" | "mov"))
+}
+
+/// the URL schemes read over the network, where a dropped connection is worth retrying
+const NETWORK_SCHEMES: [&str; 7] = ["http", "https", "ftp", "rtmp", "rtmps", "rtsp", "srt"];
+
+/// whether an input is a URL read over the network, rather than a local file
+pub fn is_network_url(input: &str) -> bool {
+    input
+        .split_once("://")
+        .is_some_and(|(scheme, _)| NETWORK_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()))
+}
+
+/// input options that make the http protocol reconnect after a dropped connection,
+/// empty for every other input since only it understands them
+pub fn reconnect_args(input: &str) -> &'static [&'static str] {
+    let is_http = input.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    });
+
+    if is_http {
+        #[rustfmt::skip]
+        return &[
+            "-reconnect",           "1",
+            "-reconnect_streamed",  "1",
+            "-reconnect_delay_max", "5",
+        ];
+    }
+    &[]
+}
+
+/// whether a container is known to refuse a codec, for the copies that commonly go wrong.
+/// anything not listed is left for ffmpeg to decide
+fn container_rejects(container: &str, codec: &str) -> bool {
+    match container {
+        "webm" => !matches!(codec, "vp8" | "vp9" | "av1" | "opus" | "vorbis"),
+        "mp4" | "m4v" => codec.starts_with("pcm_") || matches!(codec, "wmv3" | "wmav2" | "flv1"),
+        "avi" => matches!(codec, "hevc" | "av1" | "vp9" | "opus" | "truehd"),
+        "gif" => codec != "gif",
+        _ => false,
+    }
+}
+
+/// input args as options for opening an input, which only understands `-key value` pairs
+fn input_options<'a>(args: impl IntoIterator<Item = &'a str>) -> ffmpeg::Dictionary<'static> {
+    let mut options = ffmpeg::Dictionary::new();
+    for (key, value) in option_pairs(args) {
+        options.set(key, value);
+    }
+    options
+}
+
+/// the options for opening an input, with the reconnect flags first so the input args can override them
+fn opening_options(input: &str, input_args: &[String]) -> ffmpeg::Dictionary<'static> {
+    input_options(
+        reconnect_args(input)
+            .iter()
+            .copied()
+            .chain(input_args.iter().map(String::as_str)),
+    )
+}
+
+/// whether an arg is a flag rather than a value, letting negative numbers through as values
+fn is_flag(arg: &str) -> bool {
+    arg.strip_prefix('-').is_some_and(|rest| {
+        !rest.is_empty() && !rest.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+    })
+}
+
+/// pairs up `-key value` args, logging the ones an options dictionary can't hold
+fn option_pairs<'a>(args: impl IntoIterator<Item = &'a str>) -> Vec<(&'a str, &'a str)> {
+    let mut pairs = Vec::new();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if !is_flag(arg) {
+            log!(
+                "input arg {arg:?} doesn't follow a flag, so it is not used when opening the input"
+            );
+            continue;
+        }
+        match args.next_if(|value| !is_flag(value)) {
+            Some(value) => pairs.push((&arg[1..], value)),
+            None => log!("input flag {arg} has no value, so it is not used when opening the input"),
+        }
+    }
+    pairs
+}
+
+/// the output containers offered to pick from, which ffmpeg goes by the extension for
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Container {
+    Mkv,
+    Mp4,
+    Mov,
+    Webm,
+    /// only holds video, without sound
+    Gif,
+}
+
+impl Container {
+    pub const ALL: [Container; 5] = [
+        Container::Mkv,
+        Container::Mp4,
+        Container::Mov,
+        Container::Webm,
+        Container::Gif,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Container::Mkv => "mkv",
+            Container::Mp4 => "mp4",
+            Container::Mov => "mov",
+            Container::Webm => "webm",
+            Container::Gif => "gif",
+        }
+    }
+
+    /// the container a path is written in, if it's one of these
+    pub fn of(path: &str) -> Option<Container> {
+        let extension = container(path)?;
+        Container::ALL
+            .into_iter()
+            .find(|container| container.extension() == extension)
+    }
+}
+
+impl Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// the lowercase extension of a path, which is what ffmpeg picks the container from
+fn container(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+/// the pixel format to convert to when re-encoding
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PixelFormat {
+    #[default]
+    Source,
+    Yuv420p,
+    Yuv422p,
+    Yuv444p,
+    Yuv420p10le,
+    /// keeps transparency, with a codec that supports it
+    Yuva420p,
+}
+
+impl PixelFormat {
+    pub const ALL: [PixelFormat; 6] = [
+        PixelFormat::Source,
+        PixelFormat::Yuv420p,
+        PixelFormat::Yuv422p,
+        PixelFormat::Yuv444p,
+        PixelFormat::Yuv420p10le,
+        PixelFormat::Yuva420p,
+    ];
+
+    /// the ffmpeg pix_fmt name, or None to keep the source format
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            PixelFormat::Source => None,
+            PixelFormat::Yuv420p => Some("yuv420p"),
+            PixelFormat::Yuv422p => Some("yuv422p"),
+            PixelFormat::Yuv444p => Some("yuv444p"),
+            PixelFormat::Yuv420p10le => Some("yuv420p10le"),
+            PixelFormat::Yuva420p => Some("yuva420p"),
+        }
+    }
+
+    /// whether converting to this format keeps an alpha channel, which the source format does
+    pub fn keeps_alpha(&self) -> bool {
+        matches!(self, PixelFormat::Source | PixelFormat::Yuva420p)
+    }
+}
+
+impl Display for PixelFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name().unwrap_or("source"))
+    }
+}
+
+/// splits arguments on whitespace like a shell would, keeping quoted parts together
+pub fn split_args(str: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+    let mut chars = str.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('\'')) => arg.get_or_insert_default().push(c),
+            ('\\', _) => match chars.next() {
+                Some(escaped) => arg.get_or_insert_default().push(escaped),
+                None => return Err("trailing backslash".to_string()),
+            },
+            (c, Some(open)) if c == open => quote = None,
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                arg.get_or_insert_default();
+            }
+            (c, None) if c.is_whitespace() => args.extend(arg.take()),
+            (c, _) => arg.get_or_insert_default().push(c),
+        }
+    }
+
+    if let Some(open) = quote {
+        return Err(format!("unclosed {open}"));
+    }
+    args.extend(arg);
+
+    Ok(args)
+}
+
+/// how many of ffmpeg's last stderr lines are kept for a failed run
+const STDERR_TAIL_LINES: usize = 10;
+
+/// runs ffmpeg with `-hide_banner` to the end, capturing what it prints. dropping the future stops it
+async fn run_ffmpeg(
+    args: impl IntoIterator<Item = impl AsRef<OsStr>>,
+) -> Result<Output, CreateError> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(CreateError::SpawnFailed)?
+        .output()
+        .await
+        .map_err(CreateError::Io)?;
+
+    if output.status.success() {
+        return Ok(output);
+    }
+    let Some(code) = output.status.code() else {
+        // there is no exit code when ffmpeg was killed by a signal
+        return Err(CreateError::Cancelled);
+    };
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr.lines().collect();
+    let stderr_tail = lines[lines.len().saturating_sub(STDERR_TAIL_LINES)..].join("\n");
+    Err(CreateError::NonZeroExit { code, stderr_tail })
+}
+
+/// whether an ffmpeg stderr line is the muxer's warning about timestamps going backwards,
+/// like "Non-monotonous DTS in output stream 0:1"
+/// or "Application provided invalid, non monotonically increasing dts to muxer"
+fn is_non_monotonic_warning(line: &str) -> bool {
+    let line = line.to_lowercase();
+    line.contains("non-monotonous dts") || line.contains("non monotonically increasing dts")
+}
+
+/// what ffmpeg warned about while creating an output that it still finished
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Finished {
+    /// the muxer complained about timestamps going backwards in a copied stream,
+    /// which glitches the output even though it was written. `Media::fix_timestamps` usually helps
+    pub non_monotonic_timestamps: bool,
+}
+
+/// why the output couldn't be created
+#[derive(Debug)]
+pub enum CreateError {
+    /// the parameters can't make an output, like a negative duration
+    Invalid(String),
+    /// ffmpeg couldn't be started, usually because it isn't installed
+    SpawnFailed(io::Error),
+    /// waiting on ffmpeg failed
+    Io(io::Error),
+    /// ffmpeg ran but failed, with the last lines it printed
+    NonZeroExit { code: i32, stderr_tail: String },
+    /// ffmpeg failed after the muxer complained about timestamps going backwards in a copied stream.
+    /// `Media::fix_timestamps` usually helps
+    NonMonotonicTimestamps,
+    /// ffmpeg was stopped before it finished
+    Cancelled,
+}
+
+impl Display for CreateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreateError::Invalid(e) => write!(f, "{e}"),
+            CreateError::SpawnFailed(e) => {
+                write!(f, "failed to start ffmpeg, is it installed? {e}")
+            }
+            CreateError::Io(e) => write!(f, "failed to wait for ffmpeg: {e}"),
+            CreateError::NonZeroExit { code, stderr_tail } if stderr_tail.is_empty() => {
+                write!(f, "ffmpeg exited with code {code}")
+            }
+            CreateError::NonZeroExit { code, stderr_tail } => {
+                write!(f, "ffmpeg exited with code {code}:\n{stderr_tail}")
+            }
+            CreateError::NonMonotonicTimestamps => {
+                write!(f, "ffmpeg failed on timestamps that go backwards")
+            }
+            CreateError::Cancelled => write!(f, "ffmpeg was stopped before finishing"),
+        }
+    }
+}
+
+impl std::error::Error for CreateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CreateError::SpawnFailed(e) | CreateError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// how far before the declared end the truncation check starts reading
+const TAIL_PROBE_SECS: f64 = 10.0;
+/// the truncation check gives up after this many packets, since the tail evidently decodes
+const TAIL_PROBE_PACKETS: usize = 5000;
+
+/// where reading the input actually stops, if that's well short of its declared `length`,
+/// which is what a partial download looks like. the context is left seeked near the end
+fn readable_length(context: &mut ffmpeg::format::context::Input, length: f64) -> Option<f64> {
+    let target = (length - TAIL_PROBE_SECS).max(0.0);
+    let ts = (target * f64::from(ffmpeg::ffi::AV_TIME_BASE)) as i64;
+    // an input that can't seek can't be checked without reading all of it
+    context.seek(ts, ..ts).ok()?;
+
+    let mut end = None::<f64>;
+    for (i, (stream, packet)) in context.packets().enumerate() {
+        if i == TAIL_PROBE_PACKETS {
+            return None;
+        }
+        let Some(pts) = packet.pts() else {
+            continue;
+        };
+        let start = match stream.start_time() {
+            ffmpeg::ffi::AV_NOPTS_VALUE => 0,
+            start => start,
+        };
+        let secs = (pts + packet.duration() - start) as f64 * f64::from(stream.time_base());
+        end = Some(end.map_or(secs, |end| end.max(secs)));
+    }
+
+    // nothing past the seek point means nothing past it can be read
+    let end = end.unwrap_or(target);
+    (length - end > (length * 0.05).max(2.0)).then_some(end)
+}
+
+/// what was learned about the input while probing it
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct InputInfo {
+    /// the duration in seconds
+    pub length: f64,
+    /// the pixel format of the best video stream, like "yuv420p10le"
+    pub pix_fmt: Option<String>,
+    /// whether that pixel format has an alpha channel, like "yuva444p" or "rgba"
+    pub has_alpha: bool,
+    /// the average frame rate of the best video stream, if it declares one
+    pub fps: Option<f64>,
+    /// the codec names of the subtitle streams, like "subrip"
+    pub sub_codecs: Vec<String>,
+
+    pub has_video: bool,
+    pub has_audio: bool,
+    pub has_subs: bool,
+    pub has_extra_streams: bool,
+
+    pub chapters: Vec<ChapterInfo>,
+    pub attachments: Vec<Attachment>,
+
+    /// how far the input can actually be read, when that's well short of `length`,
+    /// like for a partial download. left out by probing, see `Media::readable_length`
+    pub readable_length: Option<f64>,
+}
+
+/// a chapter marker of the input, in microseconds
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChapterInfo {
+    /// counted from 1, for chapters without a title
+    pub number: usize,
+    pub title: Option<String>,
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Display for ChapterInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.title {
+            Some(title) => write!(f, "{}. {title}", self.number),
+            None => write!(f, "chapter {}", self.number),
+        }
+    }
+}
+
+impl InputInfo {
+    pub fn has_bitmap_subs(&self) -> bool {
+        self.sub_codecs
+            .iter()
+            .any(|codec| BITMAP_SUBTITLES.contains(&codec.as_str()))
+    }
+
+    /// audio-only inputs get a waveform instead of image previews
+    pub fn is_audio_only(&self) -> bool {
+        self.has_audio && !self.has_video
+    }
+
+    /// whether the video uses more than 8 bits per component, which many players can't handle
+    pub fn is_high_bit_depth(&self) -> bool {
+        self.pix_fmt.as_deref().is_some_and(|name| {
+            // the depth is the number before the endianness suffix, like the 10 in yuv420p10le
+            let name = name
+                .strip_suffix("le")
+                .or_else(|| name.strip_suffix("be"))
+                .unwrap_or_default();
+            let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+
+            name[name.len() - digits..]
+                .parse::<u32>()
+                .is_ok_and(|depth| depth > 8)
+        })
+    }
+}
+
+/// a coherent set of stream toggles, for users who don't want to reason about each one
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StreamPreset {
+    /// every stream, including ones ffmpeg wouldn't pick by default (`-map 0`)
+    Everything,
+    VideoAudio,
+    AudioOnly,
+    VideoOnly,
+}
+
+impl StreamPreset {
+    pub const ALL: [StreamPreset; 4] = [
+        StreamPreset::Everything,
+        StreamPreset::VideoAudio,
+        StreamPreset::AudioOnly,
+        StreamPreset::VideoOnly,
+    ];
+
+    /// the video, audio, subtitle and extra stream toggles for this preset,
+    /// limited to what the input has
+    fn toggles(&self, info: &InputInfo) -> (bool, bool, bool, bool) {
+        let (video, audio, subs, extra) = match self {
+            StreamPreset::Everything => (true, true, true, true),
+            StreamPreset::VideoAudio => (true, true, false, false),
+            StreamPreset::AudioOnly => (false, true, false, false),
+            StreamPreset::VideoOnly => (true, false, false, false),
+        };
+
+        (
+            video && info.has_video,
+            audio && info.has_audio,
+            subs && info.has_subs,
+            extra && info.has_extra_streams,
+        )
+    }
+
+    pub fn apply(&self, media: &mut Media, info: &InputInfo) {
+        (
+            media.use_video,
+            media.use_audio,
+            media.use_subs,
+            media.use_extra_streams,
+        ) = self.toggles(info);
+    }
+
+    /// the preset matching the current toggles, if any
+    pub fn matching(media: &Media, info: &InputInfo) -> Option<StreamPreset> {
+        let current = (
+            media.use_video,
+            media.use_audio,
+            media.use_subs,
+            media.use_extra_streams,
+        );
+
+        StreamPreset::ALL
+            .into_iter()
+            .find(|preset| preset.toggles(info) == current)
+    }
+}
+
+impl Display for StreamPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamPreset::Everything => write!(f, "everything"),
+            StreamPreset::VideoAudio => write!(f, "video + audio only"),
+            StreamPreset::AudioOnly => write!(f, "audio only"),
+            StreamPreset::VideoOnly => write!(f, "video only"),
+        }
+    }
+}
+
+/// a progress report from a running ffmpeg job
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Progress {
+    /// seconds of output written so far
+    pub out_time: f64,
+    /// processing speed as a multiple of realtime, if ffmpeg knows it yet
+    pub speed: Option<f64>,
+}
+
+impl Progress {
+    /// how much of an output `total` seconds long is written, from 0 to 1
+    pub fn fraction(&self, total: f64) -> f64 {
+        if total > 0.0 {
+            (self.out_time / total).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// another output written in the same run, like a smaller copy for sharing next to an archive
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ExtraOutput {
+    pub output: String,
+    pub video_codec: VideoCodec,
+}
+
+/// a second input for before/after comparisons
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Compare {
+    pub input: String,
+    pub layout: CompareLayout,
+}
+
+/// how the two inputs of a comparison share the frame
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareLayout {
+    #[default]
+    SideBySide,
+    PictureInPicture,
+}
+
+impl CompareLayout {
+    pub const ALL: [CompareLayout; 2] =
+        [CompareLayout::SideBySide, CompareLayout::PictureInPicture];
+
+    /// the filtergraph that combines both inputs' video, without an output label
+    fn filter(&self) -> &'static str {
+        match self {
+            // hstack needs equal heights
+            CompareLayout::SideBySide => {
+                "[0:v]scale=-2:720,setsar=1[a];[1:v]scale=-2:720,setsar=1[b];[a][b]hstack=inputs=2"
+            }
+            CompareLayout::PictureInPicture => {
+                "[1:v]scale=480:-2[pip];[0:v][pip]overlay=W-w-20:H-h-20"
+            }
+        }
+    }
+}
+
+impl Display for CompareLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompareLayout::SideBySide => write!(f, "side by side"),
+            CompareLayout::PictureInPicture => write!(f, "picture in picture"),
+        }
+    }
+}
+
+/// an image, like a logo, drawn over a corner of the video
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Watermark {
+    pub image: String,
+    pub corner: Corner,
+    /// from 0 for invisible to 1 for the image's own opacity
+    pub opacity: f64,
+}
+
+impl Watermark {
+    /// the filtergraph that draws the image from input `image_input` over the video labeled `base`,
+    /// without an output label
+    fn filter(&self, image_input: usize, base: &str) -> String {
+        format!(
+            "[{image_input}:v]format=rgba,colorchannelmixer=aa={}[logo];{base}[logo]overlay={}",
+            self.opacity,
+            self.corner.position()
+        )
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    /// the overlay filter's position, 20 pixels in from the edges
+    fn position(&self) -> &'static str {
+        match self {
+            Corner::TopLeft => "20:20",
+            Corner::TopRight => "W-w-20:20",
+            Corner::BottomLeft => "20:H-h-20",
+            Corner::BottomRight => "W-w-20:H-h-20",
+        }
+    }
+}
+
+impl Display for Corner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Corner::TopLeft => write!(f, "top left"),
+            Corner::TopRight => write!(f, "top right"),
+            Corner::BottomLeft => write!(f, "bottom left"),
+            Corner::BottomRight => write!(f, "bottom right"),
+        }
+    }
+}
+
+/// added time around the range, so the output fills a fixed length, like an ad slot
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Padding {
+    /// the output's whole length in seconds
+    pub length: f64,
+    pub placement: PadPlacement,
+    pub fill: PadFill,
+}
+
+impl Padding {
+    /// the seconds added before and after a range of `dur` seconds
+    pub fn amounts(&self, dur: f64) -> (f64, f64) {
+        let added = (self.length - dur).max(0.0);
+        match self.placement {
+            PadPlacement::Before => (added, 0.0),
+            PadPlacement::After => (0.0, added),
+            PadPlacement::Both => (added / 2.0, added / 2.0),
+        }
+    }
+
+    fn video_filter(&self, dur: f64) -> String {
+        let (before, after) = self.amounts(dur);
+        let mode = match self.fill {
+            PadFill::Black => "add",
+            PadFill::Freeze => "clone",
+        };
+        format!(
+            "tpad=start_duration={before}:stop_duration={after}:start_mode={mode}:stop_mode={mode}"
+        )
+    }
+
+    /// silence for the same time as the video, which is delayed in milliseconds
+    fn audio_filter(&self, dur: f64) -> String {
+        let (before, after) = self.amounts(dur);
+        [
+            (before > 0.0).then(|| format!("adelay=delays={}:all=1", (before * 1000.0).round())),
+            (after > 0.0).then(|| format!("apad=pad_dur={after}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PadPlacement {
+    Before,
+    #[default]
+    After,
+    /// half before and half after
+    Both,
+}
+
+impl PadPlacement {
+    pub const ALL: [PadPlacement; 3] = [
+        PadPlacement::Before,
+        PadPlacement::After,
+        PadPlacement::Both,
+    ];
+}
+
+impl Display for PadPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PadPlacement::Before => write!(f, "before"),
+            PadPlacement::After => write!(f, "after"),
+            PadPlacement::Both => write!(f, "before and after"),
+        }
+    }
+}
+
+/// what the added video shows, the audio is always silent
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PadFill {
+    #[default]
+    Black,
+    /// the first or last frame of the range
+    Freeze,
+}
+
+impl PadFill {
+    pub const ALL: [PadFill; 2] = [PadFill::Black, PadFill::Freeze];
+}
+
+impl Display for PadFill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PadFill::Black => write!(f, "black"),
+            PadFill::Freeze => write!(f, "frozen frames"),
+        }
+    }
+}
+
+/// what to do when the output already exists
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputPolicy {
+    /// refuse, so the GUI can ask first
+    #[default]
+    Ask,
+    Overwrite,
+    /// write next to it with a number appended instead
+    Version,
+}
+
+impl OutputPolicy {
+    pub const ALL: [OutputPolicy; 3] = [
+        OutputPolicy::Ask,
+        OutputPolicy::Overwrite,
+        OutputPolicy::Version,
+    ];
+}
+
+impl Display for OutputPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputPolicy::Ask => write!(f, "ask"),
+            OutputPolicy::Overwrite => write!(f, "overwrite"),
+            OutputPolicy::Version => write!(f, "add a number"),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Media {
+    /// in microseconds, see [`micros`]. job specs have them in seconds
+    #[serde(with = "seconds")]
+    pub start: i64,
+    #[serde(with = "seconds")]
+    pub dur: i64,
+
+    pub input: String,
+    pub output: String,
+
+    pub use_video: bool,
+    pub use_audio: bool,
+    pub use_subs: bool,
+    pub use_extra_streams: bool,
+
+    pub video_codec: VideoCodec,
+    pub pix_fmt: PixelFormat,
+
+    pub split: Split,
+    /// keep the input's timestamps instead of starting the output at zero,
+    /// so parts can be concatenated back together
+    pub copy_ts: bool,
+    /// regenerate missing timestamps and write packets strictly in order,
+    /// for inputs whose copied streams have timestamps going backwards
+    pub fix_timestamps: bool,
+    /// let every stream run for the whole duration, instead of ending the output with the shortest.
+    /// copied streams end on their own packet boundaries, so they rarely end together
+    pub keep_stream_tails: bool,
+
+    /// only applied when re-encoding
+    pub crop: Option<Crop>,
+
+    /// leave out the input's chapter markers, which are otherwise shifted by the trim
+    pub drop_chapters: bool,
+
+    pub output_policy: OutputPolicy,
+
+    /// a second input to show next to this one, which forces a re-encode
+    pub compare: Option<Compare>,
+
+    /// drawn over the video, which forces a re-encode
+    pub watermark: Option<Watermark>,
+
+    /// more outputs made from the same read of the input, with the same settings except for
+    /// the video codec. they aren't split
+    pub extra_outputs: Vec<ExtraOutput>,
+
+    /// seconds to delay the audio by relative to the video, negative to play it earlier
+    pub audio_offset: f64,
+
+    /// fills the output out to a fixed length, which forces a re-encode
+    pub padding: Option<Padding>,
+
+    pub sub_codec: SubtitleCodec,
+
+    /// run ffmpeg at a lower CPU and IO priority, to keep the machine responsive
+    pub low_priority: bool,
+
+    /// only applied to MP4/MOV outputs
+    pub mp4_layout: Mp4Layout,
+
+    /// the constant rate factor when re-encoding, lower for better quality.
+    /// the encoder's default when None, and ignored by encoders that don't take one
+    pub crf: Option<u8>,
+
+    /// seconds between keyframes when re-encoding, left to the encoder when None
+    pub keyframe_interval: Option<f64>,
+    /// also force keyframes at exact multiples of the interval, so segments line up
+    pub force_keyframes: bool,
+    /// the input's frame rate, filled in while probing, which the interval is converted with
+    #[serde(skip)]
+    pub fps: Option<f64>,
+    /// the index of the video stream the previews are decoded from, filled in while probing
+    #[serde(skip)]
+    pub video_stream: Option<usize>,
+
+    /// options for reading the input, like `-probesize 100M`, which go before its `-i`
+    pub input_args: Vec<String>,
+
+    /// the codec names of the mapped video and audio streams, like "hevc", filled in while probing
+    #[serde(skip)]
+    pub source_video_codec: Option<String>,
+    #[serde(skip)]
+    pub source_audio_codec: Option<String>,
+}
+
+impl Media {
+    /// starts building a Media that trims `input` into `output`.
+    /// unless changed, the whole input is used with every stream it has
+    pub fn builder(input: impl Into<String>, output: impl Into<String>) -> MediaBuilder {
+        MediaBuilder {
+            media: Media {
+                input: input.into(),
+                output: output.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// writes everything that decides the output to a job spec, which `load_job` reads back.
+    /// what is learned by probing is left out, since it's probed again on loading
+    pub fn save_job(&self, path: &Path) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, contents)
+    }
+
+    /// reads a job spec, where leaving a field out keeps its default,
+    /// but a field that isn't known is refused instead of being ignored
+    pub fn load_job(path: &Path) -> Result<Media, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+        let media: Media = serde_json::from_str(&contents)
+            .map_err(|e| format!("{} isn't a valid job spec: {e}", path.display()))?;
+        media
+            .validate()
+            .map_err(|e| format!("{} isn't a valid job spec: {e}", path.display()))?;
+        Ok(media)
+    }
+
+    /// uses the parameters and the input to create the output,
+    /// calling `on_progress` whenever ffmpeg reports how far it got
+    pub async fn run(
+        mut self,
+        mut on_progress: impl FnMut(Progress),
+    ) -> Result<Finished, CreateError> {
+        self.validate().map_err(CreateError::Invalid)?;
+        self.validate_copy().map_err(CreateError::Invalid)?;
+        self.resolve_output().map_err(CreateError::Invalid)?;
+        // ffmpeg's own error for this only says it can't open the file
+        if let Some(dir) = iter::once(self.first_output())
+            .chain(
+                self.extra_outputs
+                    .iter()
+                    .map(|extra| PathBuf::from(&extra.output)),
+            )
+            .find_map(|output| missing_dir(&output))
+        {
+            return Err(CreateError::Invalid(format!(
+                "the output folder {} doesn't exist",
+                dir.display()
+            )));
+        }
+
+        let args = self.build_args();
+        log!("running ffmpeg {}", args.join(" "));
+
+        // the output policy was already applied, so ffmpeg shouldn't stop to ask
+        let mut child = ffmpeg_command(self.low_priority)
+            .args(["-y", "-progress", "pipe:1", "-nostats"])
+            .args(&args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // dropping the run cancels it
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(CreateError::SpawnFailed)?;
+
+        // read alongside stdout, since ffmpeg blocks once either pipe is full
+        let stderr = child.stderr.take().map(|stderr| {
+            smol::spawn(async move {
+                let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+                let mut non_monotonic = false;
+                let mut lines = BufReader::new(stderr).lines();
+                while let Some(Ok(line)) = lines.next().await {
+                    // still shown on the terminal, like before it was captured
+                    eprintln!("{line}");
+                    non_monotonic |= is_non_monotonic_warning(&line);
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+                (Vec::from(tail).join("\n"), non_monotonic)
+            })
+        });
+
+        if let Some(stdout) = child.stdout.take() {
+            let mut lines = BufReader::new(stdout).lines();
+            let mut progress = Progress::default();
+
+            // each block of key=value lines ends with a "progress" key
+            while let Some(Ok(line)) = lines.next().await {
+                match line.split_once('=') {
+                    Some(("out_time_us", value)) => {
+                        if let Ok(us) = value.parse::<i64>() {
+                            progress.out_time = us as f64 / 1_000_000.0;
+                        }
+                    }
+                    Some(("speed", value)) => {
+                        progress.speed = value.trim().trim_end_matches('x').parse().ok();
+                    }
+                    Some(("progress", _)) => on_progress(progress),
+                    _ => {}
+                }
+            }
+        }
+
+        let status = child.status().await.map_err(CreateError::Io)?;
+        let (stderr_tail, non_monotonic) = match stderr {
+            Some(task) => task.await,
+            None => (String::new(), false),
+        };
+
+        // only offered when the fix isn't already on, since there's nothing more to suggest then
+        let suggest_fix = non_monotonic && !self.fix_timestamps;
+        if suggest_fix {
+            log!("ffmpeg warned about non-monotonic timestamps");
+        }
+
+        if status.success() {
+            Ok(Finished {
+                non_monotonic_timestamps: suggest_fix,
+            })
+        } else if suggest_fix && status.code().is_some() {
+            Err(CreateError::NonMonotonicTimestamps)
+        } else if let Some(code) = status.code() {
+            Err(CreateError::NonZeroExit { code, stderr_tail })
+        } else {
+            // there is no exit code when ffmpeg was killed by a signal
+            Err(CreateError::Cancelled)
+        }
+    }
+
+    /// runs with the chosen codec, then with each of the fallbacks in turn while they fail,
+    /// returning the codec that worked along with what ffmpeg warned about. hardware encoders can exist in the build but fail to
+    /// initialize on this machine, so only those are given up on, and only if ffmpeg ran and wasn't stopped
+    pub async fn run_with_fallbacks(
+        mut self,
+        fallbacks: &[VideoCodec],
+        mut on_progress: impl FnMut(Progress),
+    ) -> Result<(VideoCodec, Finished), CreateError> {
+        let mut codecs = vec![self.video_codec];
+        // copying has no encoder to fail
+        if self.video_codec.encoder().is_some() {
+            for codec in fallbacks {
+                if codec.encoder().is_some() && !codecs.contains(codec) {
+                    codecs.push(*codec);
+                }
+            }
+        }
+
+        // resolved up front, so every attempt writes to the same path
+        self.resolve_output().map_err(CreateError::Invalid)?;
+
+        let mut codecs = codecs.into_iter().peekable();
+        while let Some(codec) = codecs.next() {
+            self.video_codec = codec;
+            match self.clone().run(&mut on_progress).await {
+                Err(e @ CreateError::NonZeroExit { .. })
+                    if codec.is_hardware() && codecs.peek().is_some() =>
+                {
+                    if let Some(next) = codecs.peek() {
+                        log!("{codec} failed, trying {next} instead: {e}");
+                    }
+                    // the failed attempt may have left a partial output behind
+                    self.output_policy = OutputPolicy::Overwrite;
+                }
+                result => return result.map(|finished| (codec, finished)),
+            }
+        }
+
+        Err(CreateError::Invalid("no encoder to try".to_string()))
+    }
+
+    /// the arguments ffmpeg gets to create the output, without running it.
+    /// the output policy isn't applied, that happens in `run`
+    pub fn build_args(&self) -> Vec<String> {
+        let seek = time_arg(self.start);
+        let dur = time_arg(self.dur);
+        let compare = self.compare.as_ref().filter(|_| self.use_video);
+
+        let mut args = Vec::new();
+        if self.use_video {
+            args.extend(self.video_codec.device_args());
+            for extra in &self.extra_outputs {
+                if extra.video_codec != self.video_codec {
+                    args.extend(extra.video_codec.device_args());
+                }
+            }
+        }
+
+        if self.copy_ts {
+            args.push("-copyts");
+        }
+
+        let input_args = reconnect_args(&self.input)
+            .iter()
+            .chain(if self.fix_timestamps {
+                ["-fflags", "+genpts"].as_slice()
+            } else {
+                &[]
+            })
+            .copied()
+            .chain(self.input_args.iter().map(String::as_str));
+
+        args.extend(input_args.clone());
+        #[rustfmt::skip]
+        args.extend([
+            "-ss",  &seek,
+            "-t",   &dur,
+            "-i",   &self.input,
+        ]);
+
+        if let Some(compare) = compare {
+            // trimmed the same way, so both sides line up
+            args.extend(reconnect_args(&compare.input));
+            #[rustfmt::skip]
+            args.extend([
+                "-ss",  &seek,
+                "-t",   &dur,
+                "-i",   &compare.input,
+            ]);
+        }
+
+        // an audio offset reads the input a second time, with its timestamps shifted by
+        // -itsoffset, and takes the audio from that copy while everything else comes from the first
+        let offset = self.audio_offset_arg();
+        if let Some(offset) = &offset {
+            args.extend(input_args);
+            #[rustfmt::skip]
+            args.extend([
+                "-itsoffset",   offset,
+                "-ss",          &seek,
+                "-t",           &dur,
+                "-i",           &self.input,
+            ]);
+        }
+
+        // read last, so the inputs before it keep their indexes
+        if let Some(watermark) = self.watermark.as_ref().filter(|_| self.use_video) {
+            args.extend(["-i", &watermark.image]);
+        }
+
+        let mut args: Vec<String> = args.into_iter().map(String::from).collect();
+        args.extend(self.output_args());
+        // every extra output is encoded from the same decoded frames, so the input is only read once
+        for extra in &self.extra_outputs {
+            let media = Media {
+                video_codec: extra.video_codec,
+                output: extra.output.clone(),
+                split: Split::Off,
+                ..self.clone()
+            };
+            args.extend(media.output_args());
+        }
+        args
+    }
+
+    /// how long the output will be in seconds, which is the range unless it's padded
+    pub fn output_dur(&self) -> f64 {
+        self.needed_padding()
+            .map_or(secs(self.dur), |padding| padding.length)
+    }
+
+    /// the padding, when the range is shorter than its length
+    fn needed_padding(&self) -> Option<Padding> {
+        self.padding
+            .filter(|padding| padding.length > secs(self.dur))
+    }
+
+    /// the codec the video is written with, since a copied stream that has to be re-encoded anyway gets libx264
+    fn encoding_codec(&self) -> VideoCodec {
+        // comparing feeds both inputs through a filtergraph, so it always re-encodes, like a watermark
+        let filter_complex = self.use_video && (self.compare.is_some() || self.watermark.is_some());
+        match self.video_codec {
+            VideoCodec::Copy if filter_complex || self.needed_padding().is_some() => {
+                VideoCodec::Libx264
+            }
+            codec => codec,
+        }
+    }
+
+    /// the seconds for -itsoffset, when the audio is offset
+    fn audio_offset_arg(&self) -> Option<String> {
+        (self.use_audio && self.audio_offset != 0.0).then(|| self.audio_offset.to_string())
+    }
+
+    /// the arguments for writing the output, after every input
+    fn output_args(&self) -> Vec<String> {
+        // copied timestamps keep counting from the input's start, which the cuts are compared with
+        let segment_option = self
+            .split
+            .segment_option(if self.copy_ts { self.start } else { 0 }, self.dur);
+        // the segment muxer takes a printf-style pattern, so literal percent signs are escaped
+        let part_pattern = part_path(Path::new(&self.output.replace('%', "%%")), "%02d");
+        // comparing feeds both inputs through a filtergraph, so it always re-encodes, like a watermark
+        let compare = self.compare.as_ref().filter(|_| self.use_video);
+        let watermark = self.watermark.as_ref().filter(|_| self.use_video);
+        let filter_complex = compare.is_some() || watermark.is_some();
+        let padding = self.needed_padding();
+        let offset = self.audio_offset_arg();
+        let encoder = self
+            .video_codec
+            .encoder()
+            .or((filter_complex || padding.is_some())
+                .then(|| VideoCodec::Libx264.encoder().unwrap_or_default()));
+        let encoding_codec = self.encoding_codec();
+        let crf = self
+            .crf
+            .filter(|_| encoding_codec.supports_crf())
+            .map(|crf| crf.to_string());
+        let keyframe_interval = self.keyframe_interval.filter(|_| encoder.is_some());
+        let gop = keyframe_interval
+            .zip(self.fps)
+            .map(|(interval, fps)| (interval * fps).round().to_string());
+        let force_key_frames = keyframe_interval
+            .filter(|_| self.force_keyframes)
+            .map(|interval| format!("expr:gte(t,n_forced*{interval})"));
+        // the default selection can pick a different video stream than the previews show,
+        // like an embedded thumbnail, so the probed one is mapped instead
+        let video_map = self
+            .video_stream
+            .map_or_else(|| "0:v:0?".to_string(), |index| format!("0:{index}"));
+
+        let filters = [
+            compare.map(|compare| compare.layout.filter().to_string()),
+            self.crop
+                .filter(|_| encoder.is_some() && compare.is_none())
+                .map(|crop| format!("crop={crop}")),
+            padding.map(|padding| padding.video_filter(secs(self.dur))),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(",");
+        // the overlay takes two inputs, so the video going into it is labeled
+        let filters = match watermark {
+            Some(watermark) => {
+                let video = format!("[{}]", video_map.trim_end_matches('?'));
+                let base = match (compare, filters.is_empty()) {
+                    (Some(_), _) => format!("{filters}[base];[base]"),
+                    (None, true) => video,
+                    (None, false) => format!("{video}{filters}[base];[base]"),
+                };
+                let image_input = 1 + compare.is_some() as usize + offset.is_some() as usize;
+                watermark.filter(image_input, &base)
+            }
+            None => filters,
+        };
+        // cropping happens in software, before frames are uploaded to a hardware encoder
+        let filters = [
+            Some(filters).filter(|filters| !filters.is_empty()),
+            self.video_codec.upload_filter().map(str::to_string),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(",");
+        let filter_graph = format!("{filters}[v]");
+
+        let audio_map = match (&offset, compare) {
+            (Some(_), Some(_)) => "2:a",
+            (Some(_), None) => "1:a",
+            (None, Some(_)) => "0:a?",
+            (None, None) => "0:a:0?",
+        };
+
+        let audio_padding = padding.map(|padding| padding.audio_filter(secs(self.dur)));
+
+        let mut args = Vec::new();
+        if self.use_audio {
+            args.push("-c:a");
+            args.push(if self.use_video && self.video_codec.is_webm() {
+                "libopus"
+            } else if audio_padding.is_some() {
+                "aac"
+            } else {
+                "copy"
+            });
+            if let Some(filter) = &audio_padding {
+                args.extend(["-af", filter]);
+            }
+        } else {
+            args.push("-an");
+        }
+
+        if self.use_video {
+            args.push("-c:v");
+            args.push(encoder.unwrap_or("copy"));
+            if compare.is_some() {
+                // mapping explicitly leaves out every other stream, except the first input's audio
+                args.extend(["-filter_complex", &filter_graph, "-map", "[v]"]);
+                if self.use_audio {
+                    args.extend(["-map", audio_map]);
+                }
+            } else if watermark.is_some() {
+                // the other streams are mapped below
+                args.extend(["-filter_complex", &filter_graph]);
+            } else if !filters.is_empty() {
+                args.push("-vf");
+                args.push(&filters);
+            }
+            // hardware encoders get their format from the upload filter instead
+            if !self.video_codec.is_hardware()
+                && encoder.is_some()
+                && let Some(name) = self.pix_fmt.name()
+            {
+                args.push("-pix_fmt");
+                args.push(name);
+            }
+            if let Some(gop) = &gop {
+                args.extend(["-g", gop]);
+            }
+            if let Some(force_key_frames) = &force_key_frames {
+                args.extend(["-force_key_frames", force_key_frames]);
+            }
+            // the chosen CRF replaces the encoder's own
+            args.extend(
+                encoding_codec
+                    .quality_args()
+                    .chunks(2)
+                    .filter(|pair| crf.is_none() || pair[0] != "-crf")
+                    .flatten(),
+            );
+            if let Some(crf) = &crf {
+                args.extend(["-crf", crf]);
+            }
+        } else {
+            args.push("-vn");
+        }
+
+        if self.use_subs {
+            args.push("-c:s");
+            args.push(self.sub_codec.resolve(&self.output).encoder());
+        } else {
+            args.push("-sn");
+        }
+
+        if (offset.is_some() || watermark.is_some()) && compare.is_none() {
+            // mapping explicitly turns off the default stream selection, so it's mimicked
+            let video_map = if watermark.is_some() {
+                "[v]"
+            } else {
+                &video_map
+            };
+            if self.use_extra_streams {
+                args.extend(["-map", "0"]);
+                if watermark.is_some() {
+                    args.extend(["-map", "-0:v", "-map", video_map]);
+                }
+                if offset.is_some() {
+                    args.extend(["-map", "-0:a", "-map", audio_map]);
+                }
+            } else {
+                args.extend(["-map", video_map, "-map", "0:s:0?", "-map", audio_map]);
+            }
+        } else if self.use_extra_streams && compare.is_none() {
+            args.push("-map");
+            args.push("0");
+        } else if compare.is_none() {
+            args.extend(["-map", &video_map, "-map", "0:a:0?", "-map", "0:s:0?"]);
+        }
+
+        args.push("-map_chapters");
+        args.push(if self.drop_chapters { "-1" } else { "0" });
+
+        if self.use_video && self.use_audio && !self.keep_stream_tails {
+            args.push("-shortest");
+        }
+        if self.fix_timestamps {
+            args.extend(["-max_interleave_delta", "0"]);
+        }
+
+        let movflags = self.mp4_layout.movflags().filter(|_| is_mp4(&self.output));
+        // the segment muxer passes options on to the muxer of each part
+        let segment_movflags = movflags.map(|flags| format!("movflags={flags}"));
+        if let Some(flags) = movflags
+            && segment_option.is_none()
+        {
+            args.extend(["-movflags", flags]);
+        }
+
+        if let Some((option, value)) = &segment_option {
+            if let Some(options) = &segment_movflags {
+                args.extend(["-segment_format_options", options]);
+            }
+
+            // splits at the first keyframe after each cut, since streams are copied
+            #[rustfmt::skip]
+            args.extend([
+                "-f",                       "segment",
+                option,                     value,
+                "-segment_start_number",    "1",
+                "-reset_timestamps",        if self.copy_ts { "0" } else { "1" },
+            ]);
+            args.push(part_pattern.to_str().unwrap_or_default());
+        } else {
+            args.push(&self.output);
+        }
+
+        args.into_iter().map(String::from).collect()
+    }
+
+    /// the first file that gets written, which is the first part when splitting
+    pub fn first_output(&self) -> PathBuf {
+        first_output(Path::new(&self.output), &self.split, self.dur)
+    }
+
+    /// every file a finished run wrote, which are the numbered parts when splitting, then the extra outputs
+    pub fn outputs(&self) -> Vec<PathBuf> {
+        let mut outputs = if self.split.splits(self.dur) {
+            (1..)
+                .map(|part| part_path(Path::new(&self.output), &format!("{part:02}")))
+                .take_while(|path| path.exists())
+                .collect()
+        } else {
+            vec![PathBuf::from(&self.output)]
+        };
+        outputs.extend(
+            self.extra_outputs
+                .iter()
+                .map(|extra| PathBuf::from(&extra.output)),
+        );
+        outputs
+    }
+
+    /// applies the output policy if the output already exists,
+    /// which can change the output to a numbered path
+    pub fn resolve_output(&mut self) -> Result<(), String> {
+        // extra outputs are only ever replaced, since they're usually regenerated copies
+        if self.output_policy != OutputPolicy::Overwrite
+            && let Some(extra) = self
+                .extra_outputs
+                .iter()
+                .find(|extra| Path::new(&extra.output).exists())
+        {
+            return Err(format!("{} already exists", extra.output));
+        }
+
+        let first = self.first_output();
+        if !first.exists() {
+            return Ok(());
+        }
+
+        match self.output_policy {
+            OutputPolicy::Ask => Err(format!("{} already exists", first.display())),
+            OutputPolicy::Overwrite => Ok(()),
+            OutputPolicy::Version => {
+                let versioned = versioned_path(Path::new(&self.output), |path| {
+                    first_output(path, &self.split, self.dur).exists()
+                });
+                log!(
+                    "{} already exists, writing to {}",
+                    first.display(),
+                    versioned.display()
+                );
+                self.output = versioned.to_string_lossy().into_owned();
+                Ok(())
+            }
+        }
+    }
+
+    /// explains why the subtitles are likely to make ffmpeg fail, before running it
+    pub fn subtitle_warning(&self, info: &InputInfo) -> Option<String> {
+        if !self.use_subs || info.sub_codecs.is_empty() {
+            return None;
+        }
+
+        let codec = self.sub_codec.resolve(&self.output);
+        let container = container(&self.output).unwrap_or_default();
+        match codec {
+            SubtitleCodec::Copy if matches!(container.as_str(), "mp4" | "m4v" | "mov") => info
+                .sub_codecs
+                .iter()
+                .any(|codec| codec != "mov_text")
+                .then(|| format!("{container} only takes mov_text subtitles, pick mov_text")),
+            SubtitleCodec::Copy if container == "webm" => info
+                .sub_codecs
+                .iter()
+                .any(|codec| codec != "webvtt")
+                .then(|| "webm only takes webvtt subtitles, pick webvtt".to_string()),
+            SubtitleCodec::Copy => None,
+            _ => info.has_bitmap_subs().then(|| {
+                format!(
+                    "image subtitles can't be converted to {codec}, copy them into mkv or leave them out"
+                )
+            }),
+        }
+    }
+
+    /// re-opens the finished output, since ffmpeg can report success for a broken file
+    pub fn verify_output(&self) -> Result<(), String> {
+        let split = self.split.splits(self.dur);
+        // only the first part is checked when splitting, and its length isn't known up front
+        let output = self.first_output();
+
+        let mut probe = Media {
+            input: output.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let info = probe
+            .update_video_params()
+            .map_err(|e| format!("output can't be opened: {e}"))?;
+
+        let missing = [
+            (self.use_video, info.has_video, "video"),
+            (self.use_audio, info.has_audio, "audio"),
+            (self.use_subs, info.has_subs, "subtitle"),
+        ]
+        .into_iter()
+        .filter(|(wanted, found, _)| *wanted && !found)
+        .map(|(_, _, kind)| kind)
+        .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(format!("output has no {} stream", missing.join(" or ")));
+        }
+
+        // copied video starts at a keyframe, so allow some slack
+        let dur = secs(self.dur);
+        if !split && (info.length - dur).abs() > (dur * 0.1).max(2.0) {
+            return Err(format!(
+                "output is {:.1} seconds long instead of {dur:.1}",
+                info.length
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// rejects times that would produce garbage `-ss`/`-t` args
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start < 0 {
+            return Err(format!("invalid start time: {}", secs(self.start)));
+        }
+        if self.dur <= 0 {
+            return Err(format!("invalid duration: {}", secs(self.dur)));
+        }
+        if !self.extra_outputs.is_empty() && self.compare.is_some() {
+            return Err("comparing can't be combined with extra outputs".to_string());
+        }
+        if !self.extra_outputs.is_empty() && self.watermark.is_some() {
+            return Err("a watermark can't be combined with extra outputs".to_string());
+        }
+        if let Some(watermark) = &self.watermark
+            && !(0.0..=1.0).contains(&watermark.opacity)
+        {
+            return Err(format!("invalid watermark opacity: {}", watermark.opacity));
+        }
+        if let Some(crf) = self.crf
+            && let codec = self.encoding_codec()
+            && let Some(max) = codec.max_crf()
+            && crf > max
+        {
+            return Err(format!(
+                "invalid CRF for {codec}: {crf}, expected at most {max}"
+            ));
+        }
+        if let Some(padding) = &self.padding {
+            if !padding.length.is_finite() || padding.length <= 0.0 {
+                return Err(format!("invalid padded length: {}", padding.length));
+            }
+            if padding.length < secs(self.dur) {
+                return Err(format!(
+                    "the range is already longer than the padded length of {} seconds",
+                    padding.length
+                ));
+            }
+        }
+        if let Some(extra) = self
+            .extra_outputs
+            .iter()
+            .find(|extra| extra.output.is_empty() || extra.output == self.output)
+        {
+            return Err(format!("invalid extra output: '{}'", extra.output));
+        }
+        self.validate_keyframe_interval()
+    }
+
+    /// the video codec to keep using once the output is in another container:
+    /// the chosen one if the container can hold it, otherwise one that re-encodes into it
+    pub fn codec_for_output(&self) -> VideoCodec {
+        let copy_rejected = self.source_video_codec.as_ref().is_some_and(|codec| {
+            container_rejects(&container(&self.output).unwrap_or_default(), codec)
+        });
+
+        match (Container::of(&self.output), self.video_codec) {
+            (Some(Container::Gif), _) => VideoCodec::Gif,
+            (Some(Container::Webm), codec) if codec.is_webm() => codec,
+            (Some(Container::Webm), VideoCodec::Copy) if !copy_rejected => VideoCodec::Copy,
+            (Some(Container::Webm), _) => VideoCodec::Vp9,
+            // a gif is only worth writing into a gif
+            (_, VideoCodec::Copy | VideoCodec::Gif) if copy_rejected => VideoCodec::Libx264,
+            (_, VideoCodec::Gif) => VideoCodec::Copy,
+            (_, codec) => codec,
+        }
+    }
+
+    /// refuses to copy a stream into a container that can't hold its codec,
+    /// since ffmpeg only says it failed to write the header
+    pub fn validate_copy(&self) -> Result<(), String> {
+        let container = container(&self.output).unwrap_or_default();
+        let copies_video = self.use_video
+            && self.video_codec.encoder().is_none()
+            && self.compare.is_none()
+            && self.watermark.is_none()
+            && self.needed_padding().is_none();
+        let copies_audio = self.use_audio
+            && !(self.use_video && self.video_codec.is_webm())
+            && self.needed_padding().is_none();
+
+        if copies_video
+            && let Some(codec) = &self.source_video_codec
+            && container_rejects(&container, codec)
+        {
+            return Err(format!(
+                "{container} can't hold {codec} video, pick a codec to re-encode it or use mkv"
+            ));
+        }
+        if copies_audio
+            && let Some(codec) = &self.source_audio_codec
+            && container_rejects(&container, codec)
+        {
+            return Err(format!(
+                "{container} can't hold {codec} audio, leave the audio out or use mkv"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// the interval has to fit at least one frame of the input
+    pub fn validate_keyframe_interval(&self) -> Result<(), String> {
+        let Some(interval) = self.keyframe_interval else {
+            return Ok(());
+        };
+
+        if !interval.is_finite() || interval <= 0.0 {
+            return Err(format!("invalid keyframe interval: {interval}"));
+        }
+        if let Some(fps) = self.fps
+            && interval * fps < 1.0
+        {
+            return Err(format!(
+                "a keyframe interval of {interval} seconds is shorter than a frame at {fps} fps"
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// the options for opening the input, see [`opening_options`]
+    fn input_options(&self) -> ffmpeg::Dictionary<'static> {
+        opening_options(&self.input, &self.input_args)
+    }
+
+    /// updates the Media with the input parameters, returning what was learned about the input.
+    /// by default, we use all streams that exist
+    pub fn update_video_params(&mut self) -> Result<InputInfo, ffmpeg::Error> {
+        // try to load the media
+        let context = ffmpeg::format::input_with_dictionary(&self.input, self.input_options())?;
+
+        let has_stream = |medium| {
+            context
+                .streams()
+                .any(|stream| stream.parameters().medium() == medium)
+        };
+
+        self.use_video = has_stream(ffmpeg::media::Type::Video);
+        self.use_audio = has_stream(ffmpeg::media::Type::Audio);
+        self.use_subs = has_stream(ffmpeg::media::Type::Subtitle);
+
+        self.use_extra_streams = context.nb_streams()
+            > self.use_video as u32 + self.use_audio as u32 + self.use_subs as u32;
+
+        let pix_fmt = context
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .and_then(|stream| {
+                ffmpeg::codec::context::Context::from_parameters(stream.parameters()).ok()
+            })
+            .and_then(|context| context.decoder().video().ok())
+            .and_then(|decoder| decoder.format().descriptor());
+        let has_alpha = pix_fmt.is_some_and(has_alpha);
+        let pix_fmt = pix_fmt.map(|descriptor| descriptor.name().to_string());
+
+        let sub_codecs = context
+            .streams()
+            .filter(|stream| stream.parameters().medium() == ffmpeg::media::Type::Subtitle)
+            .map(|stream| stream.parameters().id().name().to_string())
+            .collect();
+
+        let fps = context
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .map(|stream| f64::from(stream.avg_frame_rate()))
+            .filter(|fps| fps.is_finite() && *fps > 0.0);
+        self.fps = fps;
+        self.video_stream = context
+            .streams()
+            .best(ffmpeg::media::Type::Video)
+            .map(|stream| stream.index());
+
+        let codec_name = |stream: ffmpeg::Stream| stream.parameters().id().name().to_string();
+        self.source_video_codec = self
+            .video_stream
+            .and_then(|index| context.stream(index))
+            .map(codec_name);
+        // the first audio stream, since that's the one mapped by default
+        self.source_audio_codec = context
+            .streams()
+            .find(|stream| stream.parameters().medium() == ffmpeg::media::Type::Audio)
+            .map(codec_name);
+
+        let chapters = context
+            .chapters()
+            .enumerate()
+            .map(|(i, chapter)| ChapterInfo {
+                number: i + 1,
+                title: chapter.metadata().get("title").map(str::to_string),
+                start: ticks_to_micros(chapter.start(), chapter.time_base()),
+                end: ticks_to_micros(chapter.end(), chapter.time_base()),
+            })
+            .collect();
+
+        let attachments = context
+            .streams()
+            .filter_map(|stream| {
+                let cover_art = stream
+                    .disposition()
+                    .contains(ffmpeg::format::stream::Disposition::ATTACHED_PIC);
+                (cover_art || stream.parameters().medium() == ffmpeg::media::Type::Attachment).then(
+                    || Attachment {
+                        index: stream.index(),
+                        filename: stream.metadata().get("filename").map(str::to_string),
+                        codec: stream.parameters().id().name().to_string(),
+                        cover_art,
+                    },
+                )
+            })
+            .collect();
+
+        // the duration is AV_NOPTS_VALUE when the container doesn't declare one
+        let length = (context.duration() as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE)).max(0.0);
+
+        Ok(InputInfo {
+            length,
+            pix_fmt,
+            has_alpha,
+            fps,
+            sub_codecs,
+
+            has_video: self.use_video,
+            has_audio: self.use_audio,
+            has_subs: self.use_subs,
+            has_extra_streams: self.use_extra_streams,
+
+            chapters,
+            attachments,
+
+            // checked apart, since it reads through the input's tail
+            readable_length: None,
+        })
+    }
+
+    /// where reading the input actually stops, if that's well short of its declared `length`,
+    /// which reads through the tail of the input, so it's checked apart from probing
+    pub fn readable_length(&self, length: f64) -> Option<f64> {
+        // a stream has no end to check, and seeking one would only read it again
+        if length <= 0.0 || is_network_url(&self.input) {
+            return None;
+        }
+        let mut context =
+            ffmpeg::format::input_with_dictionary(&self.input, self.input_options()).ok()?;
+
+        let readable = readable_length(&mut context, length)?;
+        log!(
+            "'{}' declares {length} seconds but can only be read up to {readable}",
+            self.input
+        );
+        Some(readable)
+    }
+}
+
+/// an ffmpeg command, wrapped in nice/ionice or given a lower priority class when asked
+fn ffmpeg_command(low_priority: bool) -> Command {
+    if !low_priority {
+        return Command::new("ffmpeg");
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut command = Command::new("nice");
+        command.args(["-n", "19", "ionice", "-c", "3", "ffmpeg"]);
+        command
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use smol::process::windows::CommandExt;
+
+        const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x4000;
+        let mut command = Command::new("ffmpeg");
+        command.creation_flags(BELOW_NORMAL_PRIORITY_CLASS);
+        command
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let mut command = Command::new("nice");
+        command.args(["-n", "19", "ffmpeg"]);
+        command
+    }
+}
+
+fn first_output(output: &Path, split: &Split, dur: i64) -> PathBuf {
+    if split.splits(dur) {
+        part_path(output, "01")
+    } else {
+        output.to_path_buf()
+    }
+}
+
+/// builds a [`Media`] from outside the GUI, see [`Media::builder`]
+#[derive(Debug, Default, Clone)]
+pub struct MediaBuilder {
+    media: Media,
+    start: f64,
+    end: Option<f64>,
+
+    use_video: Option<bool>,
+    use_audio: Option<bool>,
+    use_subs: Option<bool>,
+    use_extra_streams: Option<bool>,
+}
+
+impl MediaBuilder {
+    /// the time in seconds to start at, 0 by default
+    pub fn start(mut self, start: f64) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// the time in seconds to stop at, the end of the input by default.
+    /// negative times count back from the end of the input
+    pub fn end(mut self, end: f64) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn video(mut self, use_video: bool) -> Self {
+        self.use_video = Some(use_video);
+        self
+    }
+
+    pub fn audio(mut self, use_audio: bool) -> Self {
+        self.use_audio = Some(use_audio);
+        self
+    }
+
+    pub fn subs(mut self, use_subs: bool) -> Self {
+        self.use_subs = Some(use_subs);
+        self
+    }
+
+    pub fn extra_streams(mut self, use_extra_streams: bool) -> Self {
+        self.use_extra_streams = Some(use_extra_streams);
+        self
+    }
+
+    pub fn video_codec(mut self, video_codec: VideoCodec) -> Self {
+        self.media.video_codec = video_codec;
+        self
+    }
+
+    pub fn pix_fmt(mut self, pix_fmt: PixelFormat) -> Self {
+        self.media.pix_fmt = pix_fmt;
+        self
+    }
+
+    pub fn split(mut self, split: Split) -> Self {
+        self.media.split = split;
+        self
+    }
+
+    pub fn copy_ts(mut self, copy_ts: bool) -> Self {
+        self.media.copy_ts = copy_ts;
+        self
+    }
+
+    pub fn fix_timestamps(mut self, fix_timestamps: bool) -> Self {
+        self.media.fix_timestamps = fix_timestamps;
+        self
+    }
+
+    /// ends the output with the shortest stream unless this is set
+    pub fn keep_stream_tails(mut self, keep_stream_tails: bool) -> Self {
+        self.media.keep_stream_tails = keep_stream_tails;
+        self
+    }
+
+    /// only applied when re-encoding
+    pub fn crop(mut self, crop: Crop) -> Self {
+        self.media.crop = Some(crop);
+        self
+    }
+
+    pub fn drop_chapters(mut self, drop_chapters: bool) -> Self {
+        self.media.drop_chapters = drop_chapters;
+        self
+    }
+
+    /// puts a second input next to this one, trimmed to the same range
+    pub fn compare(mut self, compare: Compare) -> Self {
+        self.media.compare = Some(compare);
+        self
+    }
+
+    /// draws an image over a corner of the video
+    pub fn watermark(mut self, watermark: Watermark) -> Self {
+        self.media.watermark = Some(watermark);
+        self
+    }
+
+    /// re-encodes at this constant rate factor, instead of the encoder's default
+    pub fn crf(mut self, crf: u8) -> Self {
+        self.media.crf = Some(crf);
+        self
+    }
+
+    /// adds time around the range until the output is `padding.length` long
+    pub fn padding(mut self, padding: Padding) -> Self {
+        self.media.padding = Some(padding);
+        self
+    }
+
+    /// delays the audio by this many seconds, negative to play it earlier
+    pub fn audio_offset(mut self, audio_offset: f64) -> Self {
+        self.media.audio_offset = audio_offset;
+        self
+    }
+
+    pub fn sub_codec(mut self, sub_codec: SubtitleCodec) -> Self {
+        self.media.sub_codec = sub_codec;
+        self
+    }
+
+    /// seconds between keyframes when re-encoding, optionally forced at exact multiples
+    pub fn keyframe_interval(mut self, interval: f64, force: bool) -> Self {
+        self.media.keyframe_interval = Some(interval);
+        self.media.force_keyframes = force;
+        self
+    }
+
+    /// options for reading the input, like `["-probesize", "100M"]`
+    pub fn input_args(mut self, input_args: Vec<String>) -> Self {
+        self.media.input_args = input_args;
+        self
+    }
+
+    /// only applied to MP4/MOV outputs, faststart by default
+    pub fn mp4_layout(mut self, mp4_layout: Mp4Layout) -> Self {
+        self.media.mp4_layout = mp4_layout;
+        self
+    }
+
+    pub fn low_priority(mut self, low_priority: bool) -> Self {
+        self.media.low_priority = low_priority;
+        self
+    }
+
+    /// refuses to replace an existing output by default
+    pub fn output_policy(mut self, output_policy: OutputPolicy) -> Self {
+        self.media.output_policy = output_policy;
+        self
+    }
+
+    /// probes the input to fill in the defaults, then checks that the times make sense
+    pub fn build(self) -> Result<Media, String> {
+        ffmpeg::init().map_err(|e| e.to_string())?;
+
+        let mut media = self.media;
+        let info = media
+            .update_video_params()
+            .map_err(|e| format!("failed to probe '{}': {e}", media.input))?;
+
+        media.use_video = self.use_video.unwrap_or(media.use_video);
+        media.use_audio = self.use_audio.unwrap_or(media.use_audio);
+        media.use_subs = self.use_subs.unwrap_or(media.use_subs);
+        media.use_extra_streams = self.use_extra_streams.unwrap_or(media.use_extra_streams);
+
+        let end = match self.end {
+            Some(end) if end < 0.0 => end + info.length,
+            Some(end) => end,
+            None => info.length,
+        };
+        // NaN would turn into a valid 0 in microseconds
+        for (name, time) in [("start", self.start), ("end", end)] {
+            if !time.is_finite() {
+                return Err(format!("invalid {name} time: {time}"));
+            }
+        }
+        media.start = micros(self.start);
+        media.dur = micros(end) - media.start;
+
+        media.validate()?;
+        Ok(media)
+    }
+}
+
+/// whether `receive_frame` gave a frame, treating the benign "Resource temporarily unavailable"
+/// error as the decoder wanting more packets;
+/// EAGAIN comes from libc since its value differs between platforms
+fn received(result: Result<(), ffmpeg::Error>) -> Result<bool, ffmpeg::Error> {
+    match result {
+        Err(ffmpeg::Error::Other {
+            errno: ffmpeg::error::EAGAIN,
+        }) => Ok(false),
+        Err(e) => Err(e),
+        Ok(()) => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the index of the first frame a stubbed decoder gives, like the preview loop
+    fn first_frame(
+        results: Vec<Result<(), ffmpeg::Error>>,
+    ) -> Result<Option<usize>, ffmpeg::Error> {
+        for (i, result) in results.into_iter().enumerate() {
+            if received(result)? {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    #[test]
+    fn eagain_from_the_decoder_is_retried() {
+        let eagain = || {
+            Err(ffmpeg::Error::Other {
+                errno: ffmpeg::error::EAGAIN,
+            })
+        };
+
+        assert_eq!(first_frame(vec![eagain(), eagain(), Ok(())]), Ok(Some(2)));
+        assert_eq!(first_frame(vec![eagain(), eagain()]), Ok(None));
+        assert_eq!(
+            first_frame(vec![eagain(), Err(ffmpeg::Error::InvalidData), Ok(())]),
+            Err(ffmpeg::Error::InvalidData)
+        );
+    }
+
+    /// a probed two second clip with every stream, trimmed from one second in
+    fn media() -> Media {
+        Media {
+            start: 1_000_000,
+            dur: 2_000_000,
+            input: "in.mkv".to_string(),
+            output: "out.mkv".to_string(),
+            use_video: true,
+            use_audio: true,
+            use_subs: true,
+            video_stream: Some(0),
+            ..Default::default()
+        }
+    }
+
+    /// the value following `flag`, if it's there
+    fn value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    }
+
+    #[test]
+    fn args_seek_before_input_and_end_with_output() {
+        let args = media().build_args();
+
+        let position = |flag: &str| args.iter().position(|arg| arg == flag).unwrap();
+        assert!(position("-ss") < position("-i"));
+        assert_eq!(value(&args, "-ss"), Some("1"));
+        assert_eq!(value(&args, "-t"), Some("2"));
+        assert_eq!(value(&args, "-i"), Some("in.mkv"));
+        assert_eq!(args.last().map(String::as_str), Some("out.mkv"));
+    }
+
+    #[test]
+    fn seeks_near_three_hours_land_on_their_frame() {
+        // 29.97 fps in the usual 1/30000 time base, where each frame is 1001 ticks
+        let time_base = ffmpeg::Rational::new(1, 30000);
+        // read back the way ffmpeg parses it, digit by digit
+        let parse = |arg: &str| {
+            let (whole, fraction) = arg.split_once('.').unwrap_or((arg, ""));
+            whole.parse::<i64>().unwrap() * 1_000_000
+                + format!("{fraction:0<6}").parse::<i64>().unwrap()
+        };
+
+        // the frames around the 3 hour mark, which is frame 323676
+        for frame in 323_600..323_700 {
+            let ts = frame * 1001;
+            let media = Media {
+                start: ticks_to_micros(ts, time_base),
+                ..media()
+            };
+            let args = media.build_args();
+            let seek = parse(value(&args, "-ss").unwrap());
+
+            assert_eq!(seek, media.start);
+            assert_eq!(micros_to_ticks(seek, time_base), ts, "frame {frame}");
+        }
+    }
+
+    #[test]
+    fn time_args_keep_every_microsecond() {
+        assert_eq!(time_arg(0), "0");
+        assert_eq!(time_arg(1_500_000), "1.5");
+        assert_eq!(time_arg(10_799_989_000), "10799.989");
+        assert_eq!(time_arg(10_799_966_667), "10799.966667");
+        assert_eq!(time_arg(-250), "-0.00025");
+    }
+
+    #[test]
+    fn keyframes_are_only_read_around_the_boundaries() {
+        let probe = |start: f64, end: f64| KeyframeProbe {
+            input: String::new(),
+            input_args: Vec::new(),
+            stream: 0,
+            start: micros(start),
+            end: micros(end),
+        };
+
+        assert_eq!(
+            probe(10.0, 3600.0).windows(),
+            [(0, micros(40.0)), (micros(3570.0), micros(3630.0))]
+        );
+        // close boundaries are read in one go
+        assert_eq!(
+            probe(100.0, 140.0).windows(),
+            [(micros(70.0), micros(170.0))]
+        );
+    }
+
+    #[test]
+    fn input_flags_without_a_value_are_skipped() {
+        let args = [
+            "-fflags",
+            "+genpts",
+            "-re",
+            "-itsoffset",
+            "-2.5",
+            "stray",
+            "-probesize",
+            "32",
+            "-nostdin",
+        ];
+
+        assert_eq!(
+            option_pairs(args),
+            [
+                ("fflags", "+genpts"),
+                ("itsoffset", "-2.5"),
+                ("probesize", "32")
+            ]
+        );
+    }
+
+    #[test]
+    fn args_copy_by_default() {
+        let args = media().build_args();
+
+        assert_eq!(value(&args, "-c:v"), Some("copy"));
+        assert_eq!(value(&args, "-c:a"), Some("copy"));
+        assert!(args.contains(&"-shortest".to_string()));
+        assert!(!args.iter().any(|arg| arg == "-vf"));
+    }
+
+    #[test]
+    fn args_map_the_probed_video_stream() {
+        let args = Media {
+            video_stream: Some(2),
+            ..media()
+        }
+        .build_args();
+
+        assert_eq!(value(&args, "-map"), Some("0:2"));
+    }
+
+    #[test]
+    fn args_map_everything_with_extra_streams() {
+        let args = Media {
+            use_extra_streams: true,
+            ..media()
+        }
+        .build_args();
+
+        assert_eq!(value(&args, "-map"), Some("0"));
+        assert_eq!(args.iter().filter(|arg| *arg == "-map").count(), 1);
+    }
+
+    #[test]
+    fn args_crop_only_when_encoding() {
+        let crop = Some("100:50:0:10".parse().unwrap());
+
+        let copied = Media { crop, ..media() }.build_args();
+        assert_eq!(value(&copied, "-vf"), None);
+
+        let encoded = Media {
+            crop,
+            video_codec: VideoCodec::Libx264,
+            keyframe_interval: Some(2.0),
+            fps: Some(25.0),
+            ..media()
+        }
+        .build_args();
+        assert_eq!(value(&encoded, "-c:v"), Some("libx264"));
+        assert_eq!(value(&encoded, "-vf"), Some("crop=100:50:0:10"));
+        assert_eq!(value(&encoded, "-g"), Some("50"));
+    }
+
+    #[test]
+    fn args_split_into_numbered_parts() {
+        let args = Media {
+            split: Split::Parts(2),
+            output: "out%.mp4".to_string(),
+            ..media()
+        }
+        .build_args();
+
+        assert_eq!(value(&args, "-f"), Some("segment"));
+        assert_eq!(value(&args, "-segment_times"), Some("1"));
+        assert_eq!(value(&args, "-segment_time"), None);
+        assert_eq!(value(&args, "-reset_timestamps"), Some("1"));
+        // the faststart flag goes through to each part
+        assert_eq!(
+            value(&args, "-segment_format_options"),
+            Some("movflags=+faststart")
+        );
+        assert_eq!(value(&args, "-movflags"), None);
+        assert_eq!(args.last().map(String::as_str), Some("out%%_part%02d.mp4"));
+    }
+
+    #[test]
+    fn parts_are_cut_at_one_less_point_than_there_are_parts() {
+        for parts in 2..=7 {
+            let args = Media {
+                split: Split::Parts(parts),
+                ..media()
+            }
+            .build_args();
+            let cuts: Vec<&str> = value(&args, "-segment_times").unwrap().split(',').collect();
+            assert_eq!(cuts.len(), parts as usize - 1, "{cuts:?}");
+        }
+
+        let cuts = |media: Media| value(&media.build_args(), "-segment_times").map(str::to_string);
+        let thirds = Media {
+            split: Split::Parts(3),
+            ..media()
+        };
+        assert_eq!(cuts(thirds.clone()).as_deref(), Some("0.666666,1.333333"));
+        // copied timestamps carry the start on
+        assert_eq!(
+            cuts(Media {
+                copy_ts: true,
+                ..thirds
+            })
+            .as_deref(),
+            Some("1.666666,2.333333")
+        );
+    }
+
+    #[test]
+    fn args_read_the_input_again_for_an_audio_offset() {
+        let args = Media {
+            audio_offset: 0.25,
+            input_args: vec!["-probesize".to_string(), "100M".to_string()],
+            ..media()
+        }
+        .build_args();
+
+        let inputs: Vec<usize> = (0..args.len()).filter(|i| args[*i] == "-i").collect();
+        assert_eq!(inputs.len(), 2);
+        // the input options come before both reads
+        assert_eq!(
+            (0..args.len()).filter(|i| args[*i] == "-probesize").count(),
+            2
+        );
+        assert_eq!(value(&args[inputs[0]..], "-itsoffset"), Some("0.25"));
+        assert!(args.windows(2).any(|pair| pair == ["-map", "1:a"]));
+    }
+
+    #[test]
+    fn args_compare_through_a_filtergraph() {
+        let args = Media {
+            compare: Some(Compare {
+                input: "other.mkv".to_string(),
+                layout: CompareLayout::SideBySide,
+            }),
+            ..media()
+        }
+        .build_args();
+
+        assert_eq!(value(&args, "-c:v"), Some("libx264"));
+        assert!(value(&args, "-filter_complex").is_some_and(|graph| graph.ends_with("[v]")));
+        assert!(args.windows(2).any(|pair| pair == ["-map", "[v]"]));
+        assert!(args.windows(2).any(|pair| pair == ["-i", "other.mkv"]));
+    }
+
+    #[test]
+    fn args_write_extra_outputs_after_the_first() {
+        let media = Media {
+            extra_outputs: vec![ExtraOutput {
+                output: "small.mp4".to_string(),
+                video_codec: VideoCodec::Libx264,
+            }],
+            ..media()
+        };
+        let args = media.build_args();
+
+        // read once, written twice
+        assert_eq!(args.iter().filter(|arg| *arg == "-i").count(), 1);
+        let first = args.iter().position(|arg| arg == "out.mkv").unwrap();
+        assert_eq!(value(&args[..first], "-c:v"), Some("copy"));
+        assert_eq!(value(&args[first..], "-c:v"), Some("libx264"));
+        assert_eq!(args.last().map(String::as_str), Some("small.mp4"));
+        assert_eq!(
+            media.outputs(),
+            [PathBuf::from("out.mkv"), PathBuf::from("small.mp4")]
+        );
+
+        let compared = Media {
+            compare: Some(Compare {
+                input: "other.mkv".to_string(),
+                layout: CompareLayout::SideBySide,
+            }),
+            ..media
+        };
+        assert!(compared.validate().is_err());
+    }
+
+    #[test]
+    fn args_read_the_watermark_after_every_other_input() {
+        let args = Media {
+            audio_offset: 0.5,
+            watermark: Some(Watermark {
+                image: "logo.png".to_string(),
+                corner: Corner::TopLeft,
+                opacity: 0.5,
+            }),
+            ..media()
+        }
+        .build_args();
+
+        let inputs: Vec<&str> = (0..args.len())
+            .filter(|i| args[*i] == "-i")
+            .map(|i| args[i + 1].as_str())
+            .collect();
+        assert_eq!(inputs, ["in.mkv", "in.mkv", "logo.png"]);
+        assert_eq!(value(&args, "-c:v"), Some("libx264"));
+        assert_eq!(
+            value(&args, "-filter_complex"),
+            Some("[2:v]format=rgba,colorchannelmixer=aa=0.5[logo];[0:0][logo]overlay=20:20[v]")
+        );
+        assert!(args.windows(2).any(|pair| pair == ["-map", "[v]"]));
+        assert!(args.windows(2).any(|pair| pair == ["-map", "1:a"]));
+    }
+
+    #[test]
+    fn containers_that_cant_take_a_copy_get_an_encoder() {
+        let media = |output: &str, video_codec| Media {
+            output: output.to_string(),
+            video_codec,
+            source_video_codec: Some("h264".to_string()),
+            ..media()
+        };
+        assert_eq!(
+            media("out.mp4", VideoCodec::Copy).codec_for_output(),
+            VideoCodec::Copy
+        );
+        assert_eq!(
+            media("out.webm", VideoCodec::Copy).codec_for_output(),
+            VideoCodec::Vp9
+        );
+        assert_eq!(
+            media("out.webm", VideoCodec::Av1Svt).codec_for_output(),
+            VideoCodec::Av1Svt
+        );
+        assert_eq!(
+            media("out.gif", VideoCodec::Libx264).codec_for_output(),
+            VideoCodec::Gif
+        );
+        assert_eq!(
+            media("out.mkv", VideoCodec::Gif).codec_for_output(),
+            VideoCodec::Copy
+        );
+    }
+
+    #[test]
+    fn crf_is_limited_by_the_encoder() {
+        let with = |video_codec, crf| Media {
+            video_codec,
+            crf: Some(crf),
+            ..media()
+        };
+
+        assert!(with(VideoCodec::Libx264, 51).validate().is_ok());
+        assert!(with(VideoCodec::Libx264, 52).validate().is_err());
+        assert!(with(VideoCodec::Vp9, 63).validate().is_ok());
+        assert!(with(VideoCodec::Av1Svt, 64).validate().is_err());
+        // hardware encoders and copying never pass it on
+        assert!(with(VideoCodec::H264Nvenc, 64).validate().is_ok());
+        assert!(with(VideoCodec::Copy, 60).validate().is_ok());
+    }
+
+    #[test]
+    fn crf_replaces_the_encoders_default() {
+        let args = Media {
+            video_codec: VideoCodec::Vp9,
+            crf: Some(18),
+            ..media()
+        }
+        .build_args();
+        assert_eq!(args.iter().filter(|arg| *arg == "-crf").count(), 1);
+        assert_eq!(value(&args, "-crf"), Some("18"));
+        assert_eq!(value(&args, "-b:v"), Some("0"));
+
+        // hardware encoders have no CRF, and copying has nothing to apply it to
+        for video_codec in [VideoCodec::H264Nvenc, VideoCodec::Copy] {
+            let args = Media {
+                video_codec,
+                crf: Some(18),
+                ..media()
+            }
+            .build_args();
+            assert_eq!(value(&args, "-crf"), None);
+        }
+    }
+
+    #[test]
+    fn padding_fills_both_streams_out_to_the_length() {
+        let padded = Media {
+            padding: Some(Padding {
+                length: 5.0,
+                placement: PadPlacement::Both,
+                fill: PadFill::Freeze,
+            }),
+            ..media()
+        };
+        let args = padded.build_args();
+        assert_eq!(value(&args, "-c:v"), Some("libx264"));
+        assert_eq!(
+            value(&args, "-vf"),
+            Some("tpad=start_duration=1.5:stop_duration=1.5:start_mode=clone:stop_mode=clone")
+        );
+        assert_eq!(value(&args, "-c:a"), Some("aac"));
+        assert_eq!(
+            value(&args, "-af"),
+            Some("adelay=delays=1500:all=1,apad=pad_dur=1.5")
+        );
+
+        // a range that already fills the length is left alone
+        let args = Media {
+            dur: 5_000_000,
+            ..padded.clone()
+        }
+        .build_args();
+        assert_eq!(value(&args, "-c:v"), Some("copy"));
+        assert_eq!(value(&args, "-af"), None);
+
+        assert!(
+            Media {
+                dur: 6_000_000,
+                ..padded
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn fixing_timestamps_regenerates_them_before_reading() {
+        let args = Media {
+            fix_timestamps: true,
+            ..media()
+        }
+        .build_args();
+        let position = |flag: &str| args.iter().position(|arg| arg == flag).unwrap();
+        assert_eq!(value(&args, "-fflags"), Some("+genpts"));
+        assert!(position("-fflags") < position("-i"));
+        assert_eq!(value(&args, "-max_interleave_delta"), Some("0"));
+
+        assert!(is_non_monotonic_warning(
+            "[mp4 @ 0x55] Non-monotonous DTS in output stream 0:1; previous: 10, current: 9"
+        ));
+        assert!(!is_non_monotonic_warning("frame=  10 fps=0.0"));
+    }
+
+    #[test]
+    fn metadata_edits_copy_the_whole_input() {
+        let edit = MetadataEdit {
+            input: "in.mp4".to_string(),
+            title: Some("Holiday".to_string()),
+            rotation: Some(90),
+        };
+        let args = edit.args(Path::new("in.media-tweak-tmp.mp4"));
+
+        let position = |flag: &str| args.iter().position(|arg| arg == flag).unwrap();
+        assert_eq!(value(&args, "-display_rotation:v:0"), Some("-90"));
+        assert!(position("-display_rotation:v:0") < position("-i"));
+        assert_eq!(value(&args, "-c"), Some("copy"));
+        assert_eq!(value(&args, "-metadata"), Some("title=Holiday"));
+        assert!(!args.iter().any(|arg| arg == "-ss" || arg == "-t"));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("in.media-tweak-tmp.mp4")
+        );
+    }
+
+    #[test]
+    fn stills_are_named_by_their_time_in_the_input() {
+        let mut stills = StillExport {
+            input: "/videos/in.mp4".to_string(),
+            start: 5_000_000,
+            dur: Some(2_000_000),
+            max_count: 20,
+            format: StillFormat::Png,
+            ..Default::default()
+        };
+        let args = stills.args(Path::new("/videos/in_stills"));
+
+        assert!(args.iter().any(|arg| arg == "-copyts"));
+        assert_eq!(value(&args, "-ss"), Some("5"));
+        assert_eq!(value(&args, "-t"), Some("2"));
+        assert_eq!(
+            value(&args, "-vf"),
+            Some("select='eq(pict_type,I)',settb=1/1000")
+        );
+        assert_eq!(value(&args, "-frames:v"), Some("20"));
+        assert_eq!(
+            args.last().map(String::as_str),
+            Some("/videos/in_stills/in_%dms.png")
+        );
+
+        stills.every = Some(10);
+        stills.dur = None;
+        let args = stills.args(Path::new("/videos/in_stills"));
+        assert_eq!(
+            value(&args, "-vf"),
+            Some("select='not(mod(n,10))',settb=1/1000")
+        );
+        assert_eq!(value(&args, "-t"), None);
+    }
+
+    #[test]
+    fn args_reconnect_only_for_http_inputs() {
+        let remote = Media {
+            input: "https://example.com/clip.mp4".to_string(),
+            ..media()
+        };
+        let args = remote.build_args();
+        let position = |flag: &str| args.iter().position(|arg| arg == flag).unwrap();
+        assert_eq!(value(&args, "-reconnect"), Some("1"));
+        assert!(position("-reconnect") < position("-i"));
+
+        assert!(!media().build_args().contains(&"-reconnect".to_string()));
+        assert!(!is_network_url("/videos/clip.mkv"));
+        assert!(is_network_url("RTSP://camera.local/stream"));
+    }
+
+    #[test]
+    fn copying_into_a_container_that_refuses_the_codec_is_blocked() {
+        let media = Media {
+            output: "out.avi".to_string(),
+            source_video_codec: Some("hevc".to_string()),
+            source_audio_codec: Some("aac".to_string()),
+            ..media()
+        };
+        assert!(media.validate_copy().is_err());
+
+        let encoded = Media {
+            video_codec: VideoCodec::Libx264,
+            ..media.clone()
+        };
+        assert!(encoded.validate_copy().is_ok());
+
+        let pcm = Media {
+            output: "out.mp4".to_string(),
+            source_video_codec: Some("h264".to_string()),
+            source_audio_codec: Some("pcm_s16le".to_string()),
+            ..media
+        };
+        assert!(pcm.validate_copy().is_err());
+    }
+
+    #[test]
+    fn jobs_round_trip_and_refuse_unknown_fields() {
+        let media = Media {
+            video_codec: VideoCodec::Vp9,
+            split: Split::MaxDuration(30.0),
+            crop: Some(Crop {
+                width: 640,
+                height: 360,
+                x: 0,
+                y: 60,
+            }),
+            // filled in while probing, so it isn't saved
+            video_stream: None,
+            ..media()
+        };
+        let json = serde_json::to_string(&media).unwrap();
+        assert_eq!(serde_json::from_str::<Media>(&json).unwrap(), media);
+
+        let typo = json.replacen("\"use_audio\"", "\"use_sound\"", 1);
+        let e = serde_json::from_str::<Media>(&typo).unwrap_err();
+        assert!(e.to_string().contains("use_sound"));
+    }
+
+    #[test]
+    fn encoder_lists_parse_by_encoder_name() {
+        assert_eq!(
+            VideoCodec::parse_list("h264_nvenc, hevc_qsv,libx264"),
+            Ok(vec![
+                VideoCodec::H264Nvenc,
+                VideoCodec::HevcQsv,
+                VideoCodec::Libx264
+            ])
+        );
+        assert_eq!(VideoCodec::parse_list(""), Ok(Vec::new()));
+        assert!(VideoCodec::parse_list("libx264, x265").is_err());
+    }
+
+    #[test]
+    fn split_args_keeps_quotes_together() {
+        assert_eq!(
+            split_args(r#"-probesize 100M  -metadata "title=a b" 'c\d' e\ f"#),
+            Ok(vec![
+                "-probesize".to_string(),
+                "100M".to_string(),
+                "-metadata".to_string(),
+                "title=a b".to_string(),
+                r"c\d".to_string(),
+                "e f".to_string(),
+            ])
+        );
+        assert_eq!(split_args(r#""""#), Ok(vec![String::new()]));
+        assert!(split_args("\"unclosed").is_err());
+    }
+
+    #[test]
+    fn padded_rows_are_packed() {
+        // 3 pixels wide, with rows padded to 16 bytes
+        let (row_len, stride) = (3 * 4, 16);
+        let data: Vec<u8> = (0..2)
+            .flat_map(|row| {
+                let mut bytes = vec![row as u8 + 1; row_len];
+                bytes.resize(stride, 0);
+                bytes
+            })
+            .collect();
+
+        let packed = packed_rows(&data, stride, row_len, 2);
+        assert_eq!(packed.len(), 2 * row_len);
+        assert!(packed[..row_len].iter().all(|byte| *byte == 1));
+        assert!(packed[row_len..].iter().all(|byte| *byte == 2));
+    }
 
-        Ok(context.duration() as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE))
+    #[test]
+    fn crop_round_trips() {
+        let crop: Crop = "1920:800:0:140".parse().unwrap();
+        assert_eq!(crop.to_string(), "1920:800:0:140");
+        assert!("1920:800".parse::<Crop>().is_err());
     }
 }
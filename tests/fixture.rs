@@ -0,0 +1,76 @@
+//! end to end checks against a tiny clip generated with ffmpeg's test sources,
+//! skipped when the ffmpeg binary isn't installed
+
+use std::{env, path::PathBuf, process::Command};
+
+use media_tweak::Media;
+
+/// renders a clip of `dur` seconds with a 10 fps test pattern, a sine tone and nothing else
+fn fixture(name: &str, dur: u32) -> Option<PathBuf> {
+    let path = env::temp_dir().join(format!("media-tweak-test-{}-{name}", std::process::id()));
+
+    let status = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args([
+            "-f",
+            "lavfi",
+            "-i",
+            &format!("testsrc=duration={dur}:size=64x48:rate=10"),
+        ])
+        .args(["-f", "lavfi", "-i", &format!("sine=duration={dur}")])
+        .args(["-c:v", "libx264", "-g", "10", "-c:a", "aac", "-shortest"])
+        .arg(&path)
+        .status();
+
+    match status {
+        Ok(status) if status.success() => Some(path),
+        _ => {
+            eprintln!("skipping, ffmpeg couldn't generate the fixture");
+            None
+        }
+    }
+}
+
+#[test]
+fn probing_finds_the_streams() {
+    let Some(input) = fixture("probe.mkv", 2) else {
+        return;
+    };
+
+    let mut media = Media {
+        input: input.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let info = media.update_video_params().unwrap();
+
+    assert!(info.has_video && info.has_audio);
+    assert!(!info.has_subs);
+    assert!((info.length - 2.0).abs() < 0.1);
+    assert_eq!(info.fps, Some(10.0));
+    // a complete file reads to its end
+    assert_eq!(media.readable_length(info.length), None);
+    assert!(media.use_video && media.use_audio && !media.use_subs);
+
+    std::fs::remove_file(input).unwrap();
+}
+
+#[test]
+fn trimming_makes_a_shorter_output() {
+    let Some(input) = fixture("trim.mkv", 4) else {
+        return;
+    };
+    let output = input.with_file_name(format!("{}_out.mkv", input.display()));
+
+    let media = Media::builder(input.to_string_lossy(), output.to_string_lossy())
+        .start(1.0)
+        .end(3.0)
+        .build()
+        .unwrap();
+    assert_eq!(media.dur, 2_000_000);
+
+    smol::block_on(media.clone().run(|_| ())).unwrap();
+    media.verify_output().unwrap();
+
+    std::fs::remove_file(input).unwrap();
+    std::fs::remove_file(output).unwrap();
+}